@@ -16,6 +16,7 @@ IDENTIFIER  = 'identifier'
 INT      	= 'int'
 FLOAT		= 'float'
 STRING      = 'string'
+JOINED_STRING = 'joinedstr'
 BYTE        = 'byte'
 SEQUENCE    = 'sequence'
 LIST        = 'list'
@@ -39,9 +40,11 @@ INDEX       = 'index'
 GENERATOR   = 'generator'
 FILTER      = 'filter'
 MAP         = 'map'
+MAPPAIR     = 'mappair'
 ANDTHEN     = 'andthen'
 BLOCK       = 'block'
 IF          = 'if'
+IFEXP       = 'ifexp'
 WHILE       = 'while'
 FOREACH     = 'foreach'
 TRY         = 'try'
@@ -53,5 +56,8 @@ ASSERT      = 'assert'
 IMPORT      = 'import'
 BREAK		= 'break'
 CONTINUE	= 'continue'
+GLOBAL      = 'global'
+NONLOCAL    = 'nonlocal'
+DELETE      = 'delete'
 CLASS       = 'class'
 ANONYMOUS_FUNCTION = 'anonymous_function'
\ No newline at end of file
@@ -132,6 +132,15 @@ class If(Control):
   def kind(self):
     return constants.IF
 
+class IfExp(Control):
+  def __init__(self, test: 'BoolOp', body: 'GastNode', orElse: 'GastNode', line, col):
+    super().__init__(None, test, body, orElse, None)
+    self.line = line
+    self.col = col
+
+  def kind(self):
+    return constants.IFEXP
+
 class ForEach(Control):
   def __init__(self, before: 'Generator', body: 'Block', orElse: 'Block', line, col):
     super().__init__(before, None, body, orElse, None)
@@ -298,6 +307,19 @@ class Byte(GastNode):
     return constants.BYTE
 
 # fixed length
+# f-strings, only the embedded expressions matter
+class JoinedStr(GastNode):
+  def __init__(self, values: 'list', line, col):
+    self.values = values
+    global count
+    self.id = count
+    count += 1
+    self.line = line
+    self.col = col
+
+  def kind(self):
+    return constants.JOINED_STRING
+
 class Sequence(GastNode):
   def __init__(self, content: 'iterable', line, col):
     self.content = content
@@ -438,15 +460,16 @@ class Call(Named):
       arg.relabel()
 
     for kwarg in self.keyword_args:
-      arg.relabel()
+      kwarg.relabel()
 
     self.id = count
     count += 1
 
 class Assign(GastNode):
-  def __init__(self, targets: 'list', value: 'Expression', line, col):
+  def __init__(self, targets: 'list', value: 'Expression', line, col, augmented=False):
     self.targets = targets
     self.value = value
+    self.augmented = augmented
     global count
     self.id = count
     count += 1
@@ -589,6 +612,42 @@ class Assert(GastNode):
   def kind(self):
     return constants.ASSERT
 
+class Global(GastNode):
+  def __init__(self, names: '[str]', line, col):
+    self.names = names
+    global count
+    self.id = count
+    count += 1
+    self.line = line
+    self.col = col
+
+  def kind(self):
+    return constants.GLOBAL
+
+class Nonlocal(GastNode):
+  def __init__(self, names: '[str]', line, col):
+    self.names = names
+    global count
+    self.id = count
+    count += 1
+    self.line = line
+    self.col = col
+
+  def kind(self):
+    return constants.NONLOCAL
+
+class Delete(GastNode):
+  def __init__(self, targets: 'list', line, col):
+    self.targets = targets
+    global count
+    self.id = count
+    count += 1
+    self.line = line
+    self.col = col
+
+  def kind(self):
+    return constants.DELETE
+
 class Import(GastNode):
   def __init__(self, module: 'str', parts: '[Pair]', into: 'str', line, col):
     self.module = module
@@ -624,6 +683,7 @@ class Slice(GastNode):
     self.target = target
     self.lower = lower
     self.upper = upper
+    self.step = step
     global count
     self.id = count
     count += 1
@@ -703,6 +763,29 @@ class Map(Stream):
     self.source.relabel()
     self.op.relabel()
 
+class MapPair(Stream):
+  def __init__(self, source: 'Iterable', key: 'Expression', value: 'Expression', line, col):
+    self.source = source
+    self.key = key
+    self.value = value
+    global count
+    self.id = count
+    count += 1
+    self.line = line
+    self.col = col
+
+  def kind(self):
+    return constants.MAPPAIR
+
+  def relabel(self):
+    global count
+    self.id = count
+    count += 1
+
+    self.source.relabel()
+    self.key.relabel()
+    self.value.relabel()
+
 class AndThen(Stream):
   def __init__(self, first: 'Stream', second: 'Stream', line, col):
     self.first = first
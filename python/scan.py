@@ -1,22 +1,77 @@
 import copy
 import gast 
+import io
 import json
+import re
+import tokenize
 import constants
 
 from ast import *
 
+SUPPRESSION = re.compile(r'#\s*fosite:\s*ignore(?:\[([^\]]*)\])?')
+
 class Scan:
   def __init__(self):
     self.count = 0
     self.loops = []
+    self.suppressions = []
+    self.tokens = []
+    self.starts = {}
 
   def to_general_form(self, code):
     t = parse(code)
+    self.suppressions = self.suppression_comments(code)
+    self.tokens = self.significant_tokens(code)
+    self.starts = {token.start: index for index, token in enumerate(self.tokens)}
     general_form = self.block(t)
+    general_form.source = code
     #print(json.dumps(general_form, sort_keys=True,
     #                 indent=2, separators=(',', ': ')))
     return general_form
 
+  def suppression_comments(self, code):
+    # '# fosite: ignore' or '# fosite: ignore[TYPE_UNSAFE, NO_RETURN]'
+    result = []
+
+    try:
+      for token in tokenize.generate_tokens(io.StringIO(code).readline):
+        if token.type != tokenize.COMMENT:
+          continue
+
+        match = SUPPRESSION.match(token.string)
+        if match is None:
+          continue
+
+        if match.group(1) is None:
+          codes = ['*']
+        else:
+          codes = [c.strip() for c in match.group(1).split(',') if c.strip()]
+
+        result.append((token.start[0], codes))
+    except tokenize.TokenError:
+      pass
+
+    return result
+
+  def significant_tokens(self, code):
+    # the tokens a node can end with, comments and indentation can't
+    skipped = (tokenize.NL, tokenize.COMMENT, tokenize.INDENT, tokenize.DEDENT, tokenize.ENDMARKER)
+
+    try:
+      return [token for token in tokenize.generate_tokens(io.StringIO(code).readline)
+              if token.type not in skipped]
+    except tokenize.TokenError:
+      return []
+
+  def suppress(self, code):
+    # comments apply to the first statement on or after their line
+    codes = []
+
+    while self.suppressions and self.suppressions[0][0] <= code.lineno:
+      codes.extend(self.suppressions.pop(0)[1])
+
+    return codes
+
   def block(self, code):
     body = []
 
@@ -26,13 +81,11 @@ class Scan:
           continue
 
         self.count += 1
-        content = self.statement(t)
-        body.extend(content)
+        body.extend(self.annotate(t))
     else:
       for _, b in iter_fields(code):
         for t in b:
-          content = self.statement(t)
-          body.extend(content)
+          body.extend(self.annotate(t))
 
     result = gast.Block(body)
 
@@ -74,6 +127,12 @@ class Scan:
       yield self.break_loop(code)
     elif type(code) is Continue:
       yield self.continue_loop(code)
+    elif type(code) is Global:
+      yield gast.Global(code.names, code.lineno, code.col_offset)
+    elif type(code) is Nonlocal:
+      yield gast.Nonlocal(code.names, code.lineno, code.col_offset)
+    elif type(code) is Delete:
+      yield self.delete(code)
     elif type(code) is FunctionDef:
       yield self.function(code)
     elif type(code) is AugAssign or type(code) is Assign:
@@ -103,17 +162,130 @@ class Scan:
     else:
       raise Exception('Unsupported node:', code)
 
+  def annotate(self, code):
+    codes = self.suppress(code)
+    content = [self.span(node, code) for node in self.statement(code)]
+
+    if codes:
+      for node in content:
+        node.ignore = codes
+
+    return content
+
+  def span(self, node, code):
+    if not hasattr(code, 'lineno') or hasattr(node, 'end_col'):
+      return node
+
+    # nodes that were lowered to a different location keep their own
+    if getattr(node, 'line', None) != code.lineno or getattr(node, 'col', None) != code.col_offset:
+      return node
+
+    return self.end_at(node, code)
+
+  def end_at(self, node, code):
+    # end positions are only available since python 3.8
+    if getattr(code, 'end_col_offset', None) is not None:
+      node.end_line = code.end_lineno
+      node.end_col = code.end_col_offset
+    else:
+      last = self.last_token(code)
+      if last is not None:
+        node.end_line, node.end_col = self.tokens[last].end
+
+    return node
+
+  def last_token(self, code):
+    # the index of the last token of a node, none if it can't be found
+    if hasattr(code, 'lineno'):
+      last = self.starts.get((code.lineno, code.col_offset))
+      if last is None:
+        return None
+    else:
+      # helpers like arguments and comprehensions have no position
+      last = None
+
+    # the parts of a string are positioned relative to the string
+    if type(code) not in (Str, Bytes, JoinedStr):
+      for child in iter_child_nodes(code):
+        child_last = self.last_token(child)
+        if child_last is not None and (last is None or child_last > last):
+          last = child_last
+
+    if last is None:
+      return None
+
+    first = self.starts[(code.lineno, code.col_offset)] if hasattr(code, 'lineno') else last
+    kind = type(code)
+
+    if self.tokens[first].string in ('(', '[', '{'):
+      last = max(last, self.closing_token(first))
+
+    if self.tokens[last].type == tokenize.STRING:
+      # implicitly concatenated strings
+      while last + 1 < len(self.tokens) and self.tokens[last + 1].type == tokenize.STRING:
+        last += 1
+
+    if kind is Attribute:
+      name = self.next_token(self.head_token(code.value, first), (code.attr,))
+      if name is not None:
+        last = max(last, name)
+    elif kind in (Call, Subscript):
+      head = self.head_token(code.func if kind is Call else code.value, first)
+      opening = self.next_token(head, ('(',) if kind is Call else ('[',))
+      if opening is not None:
+        last = max(last, self.closing_token(opening))
+    elif isinstance(code, stmt) and not hasattr(code, 'body'):
+      # simple statements run up to the end of their line or a semicolon
+      while last + 1 < len(self.tokens):
+        token = self.tokens[last + 1]
+        if token.type == tokenize.NEWLINE or token.string == ';':
+          break
+        last += 1
+
+    return last
+
+  def head_token(self, code, default):
+    last = self.last_token(code)
+    return default if last is None else last
+
+  def next_token(self, index, strings):
+    for current in range(index + 1, len(self.tokens)):
+      if self.tokens[current].string in strings:
+        return current
+
+    return None
+
+  def closing_token(self, index):
+    # the bracket that matches the one at the given index
+    depth = 0
+
+    for current in range(index, len(self.tokens)):
+      token = self.tokens[current]
+      if token.type == tokenize.OP and token.string in ('(', '[', '{'):
+        depth += 1
+      elif token.type == tokenize.OP and token.string in (')', ']', '}'):
+        depth -= 1
+        if depth == 0:
+          return current
+
+    return index
+
   def expression(self, code):
+    return self.span(self._expression(code), code)
+
+  def _expression(self, code):
     if type(code) is Call:
       return self.call(code)
     elif type(code) is IfExp:
-      return self.conditional(code)
+      return self.conditional_expression(code)
     elif type(code) is Attribute:
       return self.attribute(code)
     elif type(code) is ListComp:
       return self.list_comprehension(code)
     elif type(code) is SetComp:
       return self.set_comprehension(code)
+    elif type(code) is DictComp:
+      return self.dict_comprehension(code)
     elif type(code) is GeneratorExp:
       return self.generator_expression(code)
     elif type(code) is comprehension:
@@ -293,7 +465,15 @@ class Scan:
       # kind of a hack to support starred variables
       left = [self.expression(target) for target in code.targets]
 
-    return gast.Assign(left, right, code.lineno, code.col_offset)
+    # mutable targets of augmented assigns get changed in place
+    augmented = type(code) is AugAssign
+
+    return gast.Assign(left, right, code.lineno, code.col_offset, augmented=augmented)
+
+  def delete(self, code):
+    targets = [self.expression(target) for target in code.targets]
+
+    return gast.Delete(targets, code.lineno, code.col_offset)
     
   def variable(self, code):
     if type(code) is Name:
@@ -307,7 +487,7 @@ class Scan:
     temp = code.value
 
     if type(temp) == Name:
-      target = gast.Identifier(temp.id, code.lineno, code.col_offset)
+      target = self.span(gast.Identifier(temp.id, code.lineno, code.col_offset), temp)
       attribute = code.attr
     else:
       target = self.expression(temp)
@@ -324,13 +504,14 @@ class Scan:
       index = self.expression(op.value)
       return gast.Index(target, index, code.lineno, code.col_offset)
     elif type(op) is Slice:
+      # missing bounds depend on the sign of the step
       if op.lower is None:
-        lower = gast.Int(0, code.lineno, code.col_offset)
+        lower = gast.Nil(code.lineno, code.col_offset)
       else:
         lower = self.expression(op.lower)
 
       if op.upper is None:
-        upper = gast.Int(0, code.lineno, code.col_offset)
+        upper = gast.Nil(code.lineno, code.col_offset)
       else:
         upper = self.expression(op.upper)
 
@@ -370,6 +551,8 @@ class Scan:
       return gast.String(code.s, code.lineno, code.col_offset)
     elif type(code) is Bytes:
       return gast.Byte(code.s, code.lineno, code.col_offset)
+    elif type(code) is JoinedStr:
+      return self.joined_string(code)
     elif type(code) is List:
       values = [self.expression(element) for element in code.elts]
       return gast.List(values, code.lineno, code.col_offset) 
@@ -396,13 +579,27 @@ class Scan:
     
     raise Exception('Unsupported node:', code)
 
+  def joined_string(self, code):
+    values = []
+
+    for value in code.values:
+      if type(value) is FormattedValue:
+        values.append(self.expression(value.value))
+
+        # the format spec can embed expressions as well
+        if value.format_spec is not None:
+          values.append(self.joined_string(value.format_spec))
+
+    return gast.JoinedStr(values, code.lineno, code.col_offset)
+
   def call(self, code):
     name = self.expression(code.func)
     args = [self.expression(arg) for arg in code.args]
     kwargs = []
 
     for arg in code.keywords:
-      keyword = arg.arg
+      # an unpacked dict has no name, ** can't be a parameter name
+      keyword = arg.arg if arg.arg is not None else '**'
       value = self.expression(arg.value)
       kwargs.append(self.argument(keyword, value))
 
@@ -454,6 +651,20 @@ class Scan:
     mapped = [gast.Map(acc, fun, code.lineno, code.col_offset)]
     return gast.Set(mapped, code.lineno, code.col_offset)
 
+  def dict_comprehension(self, code):
+    generators = [self.expression(generator) for generator in code.generators]
+
+    acc = generators[0]
+
+    for i in range(1, len(generators)):
+      acc = gast.AndThen(acc, generators[i], code.lineno, code.col_offset)
+
+    key = self.expression(code.key)
+    value = self.expression(code.value)
+
+    mapped = [gast.MapPair(acc, key, value, code.lineno, code.col_offset)]
+    return gast.Dictionary(mapped, code.lineno, code.col_offset)
+
   def generator_expression(self, code):
     generators = [self.expression(generator) for generator in code.generators]
 
@@ -476,6 +687,13 @@ class Scan:
 
     return gast.If(test, body, orElse, code.lineno, code.col_offset)
 
+  def conditional_expression(self, code):
+    test = self.expression(code.test)
+    body = self.expression(code.body)
+    orElse = self.expression(code.orelse)
+
+    return gast.IfExp(test, body, orElse, code.lineno, code.col_offset)
+
   def for_loop(self, code):
     class Box:
       pass
@@ -483,7 +701,9 @@ class Scan:
     source = self.expression(code.iter)
     target = self.expression(code.target)
 
+    # the header of the loop runs up to the end of its iterable
     generator = gast.Generator(source, target, code.lineno, code.col_offset)
+    self.end_at(generator, code.iter)
 
     box = Box()
     self.loops.append(box)
@@ -624,10 +844,11 @@ class Scan:
             # _not_ an actual identifier, conform to the argument syntax used when calling
             identifier = arg.arg
 
-            default = gast.Nil(arg.lineno, arg.col_offset)
+            # an empty block marks a required argument
+            default = gast.Block([])
 
             if i >= len(args) - len(defaults):
-                offset = len(args) - i - 1
+                offset = i - (len(args) - len(defaults))
                 default = defaults[offset]
 
                 default = self.literal(default)
@@ -653,7 +874,7 @@ class Scan:
             identifier = arg.arg
             
             if default is None:
-                default = gast.Nil(arg.lineno, arg.col_offset)
+                default = gast.Block([])
             else:
                 default = self.literal(default)
         
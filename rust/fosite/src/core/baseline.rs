@@ -0,0 +1,252 @@
+use rustc_serialize::json::Json;
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::prelude::*;
+
+use super::GastID;
+use super::GastNode;
+use super::PathID;
+use super::MessageContent;
+use super::Sources;
+use super::RULES;
+use super::rule_index;
+
+type Nodes = HashMap<GastID, GastNode>;
+
+// previously reported messages, identified by their code, content hash
+// and a fingerprint of the location that survives line shifts
+pub struct Baseline {
+    entries: Vec<(i16, u64, u64)>,
+}
+
+impl Baseline {
+    pub fn new() -> Baseline {
+        Baseline {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Baseline, String> {
+        let mut text = String::new();
+
+        match File::open(path) {
+            Ok(mut file) => {
+                if let Err(why) = file.read_to_string(&mut text) {
+                    return Err(format!("{}: {}", path, why));
+                }
+            }
+            Err(why) => return Err(format!("{}: {}", path, why)),
+        }
+
+        let json = Json::from_str(&text).map_err(|why| format!("{}: {}", path, why))?;
+        let entries = match json.find("entries").and_then(|entries| entries.as_array()) {
+            Some(entries) => entries,
+            None => return Err(format!("{}: missing entries", path)),
+        };
+
+        let mut baseline = Baseline::new();
+
+        for entry in entries {
+            let rule = entry.find("rule").and_then(|r| r.as_string());
+            let hash = entry.find("hash").and_then(|h| h.as_string());
+            let fingerprint = entry.find("fingerprint").and_then(|f| f.as_string());
+
+            let code = match rule.and_then(|rule| RULES.iter().find(|&&(_, name, _)| name == rule)) {
+                Some(&(code, _, _)) => code,
+                None => return Err(format!("{}: unknown rule {:?}", path, rule)),
+            };
+
+            match (hash.map(parse_hex), fingerprint.map(parse_hex)) {
+                (Some(Some(hash)), Some(Some(fingerprint))) => baseline.add(code, hash, fingerprint),
+                _ => return Err(format!("{}: invalid entry {}", path, entry)),
+            }
+        }
+
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let entries = self.entries.iter()
+            .map(|&(code, hash, fingerprint)| {
+                let rule = rule_index(code).map(|index| RULES[index].1).unwrap_or("");
+
+                let mut entry = BTreeMap::new();
+                entry.insert("rule".to_owned(), Json::String(rule.to_owned()));
+                entry.insert("hash".to_owned(), Json::String(format!("{:016x}", hash)));
+                entry.insert("fingerprint".to_owned(), Json::String(format!("{:016x}", fingerprint)));
+                Json::Object(entry)
+            })
+            .collect();
+
+        let mut baseline = BTreeMap::new();
+        baseline.insert("version".to_owned(), Json::U64(1));
+        baseline.insert("entries".to_owned(), Json::Array(entries));
+
+        let mut file = File::create(path).map_err(|why| format!("{}: {}", path, why))?;
+        writeln!(file, "{}", Json::Object(baseline).pretty()).map_err(|why| format!("{}: {}", path, why))
+    }
+
+    pub fn add(&mut self, code: i16, hash: u64, fingerprint: u64) {
+        self.entries.push((code, hash, fingerprint));
+    }
+
+    // every entry only covers a single message
+    pub fn take(&mut self, code: i16, hash: u64, fingerprint: u64) -> bool {
+        let position = self.entries.iter()
+            .position(|&(c, h, _)| c == code && h == hash)
+            .or_else(|| self.entries.iter().position(|&(c, _, f)| c == code && f == fingerprint));
+
+        match position {
+            Some(index) => {
+                self.entries.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Default for Baseline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// the message and the text of its line, without row or column
+pub fn fingerprint(content: &MessageContent, sources: &Sources, nodes: &Nodes, node: &PathID) -> u64 {
+    let mut s = Fnv::new();
+    s.write(&content.code().to_le_bytes());
+
+    if let Some(summary) = content.summary(nodes, node) {
+        s.write(summary.as_bytes());
+    }
+
+    // keeps the summary and the line apart
+    s.write(&[0xff]);
+
+    let line = node.last()
+        .and_then(|source| sources.get(source))
+        .and_then(|&(row, _)| sources.get_line(row));
+
+    if let Some(line) = line {
+        s.write(line.trim().as_bytes());
+    }
+
+    s.finish()
+}
+
+// 64 bit FNV-1a, fingerprints get stored so they can't depend on the
+// hasher of the standard library, which changes between releases
+struct Fnv {
+    state: u64,
+}
+
+impl Fnv {
+    fn new() -> Fnv {
+        Fnv {
+            state: 0xcbf2_9ce4_8422_2325,
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= u64::from(*byte);
+            self.state = self.state.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+fn parse_hex(text: &str) -> Option<u64> {
+    u64::from_str_radix(text, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{OutOfBounds, HETERO_COLLECTION, OUT_OF_BOUNDS};
+
+    use std::env;
+
+    // a single message, attached to node 1 at the given row
+    fn fingerprint_at(text: &str, row: i16) -> u64 {
+        let content = OutOfBounds::new("xs".to_owned(), vec![]);
+        let mut sources = Sources::new();
+        sources.insert(1, (row, 0), None);
+        sources.set_text(text);
+
+        fingerprint(&content, &sources, &Nodes::new(), &vec![1])
+    }
+
+    #[test]
+    fn fingerprint_survives_line_shifts() {
+        let before = fingerprint_at("print(xs[3])\n", 1);
+        let after = fingerprint_at("\n\n    print(xs[3])\n", 3);
+
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn fingerprint_depends_on_the_line() {
+        let before = fingerprint_at("print(xs[3])\n", 1);
+        let after = fingerprint_at("print(xs[4])\n", 1);
+
+        assert!(before != after);
+    }
+
+    #[test]
+    fn fingerprint_is_stable() {
+        // stored in baseline files, so this may never change
+        assert_eq!(fingerprint_at("print(xs[3])\n", 1), 0xedaa_6eb0_05df_102c);
+    }
+
+    #[test]
+    fn take_matches_hash_then_fingerprint() {
+        let mut baseline = Baseline::new();
+        baseline.add(OUT_OF_BOUNDS, 1, 10);
+        baseline.add(OUT_OF_BOUNDS, 2, 20);
+
+        assert!(baseline.take(OUT_OF_BOUNDS, 2, 99));
+        assert!(baseline.take(OUT_OF_BOUNDS, 99, 10));
+        assert!(baseline.is_empty());
+    }
+
+    #[test]
+    fn take_uses_every_entry_once() {
+        let mut baseline = Baseline::new();
+        baseline.add(OUT_OF_BOUNDS, 1, 10);
+
+        assert!(!baseline.take(HETERO_COLLECTION, 1, 10));
+        assert!(baseline.take(OUT_OF_BOUNDS, 1, 10));
+        assert!(!baseline.take(OUT_OF_BOUNDS, 1, 10));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = env::temp_dir().join("fosite-baseline-test.json");
+        let path = path.to_str().unwrap();
+
+        let mut baseline = Baseline::new();
+        baseline.add(OUT_OF_BOUNDS, 0xdead_beef, 0xffff_ffff_ffff_ffff);
+        baseline.save(path).unwrap();
+
+        let mut loaded = Baseline::load(path).unwrap();
+        let _ = ::std::fs::remove_file(path);
+
+        assert_eq!(loaded.len(), 1);
+        assert!(loaded.take(OUT_OF_BOUNDS, 0xdead_beef, 0));
+    }
+}
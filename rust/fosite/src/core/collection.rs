@@ -54,6 +54,36 @@ impl CollectionChunk {
         self.representants.insert(path, repr);
     }
 
+    // the same representants, none of them have to be there
+    fn optional(&self) -> CollectionChunk {
+        let mut chunk = CollectionChunk::empty();
+
+        for (path, repr) in self.iter() {
+            chunk.add_representant(path.clone(), Representant::new(repr.object, repr.kind, Some(0), repr.maximum));
+        }
+
+        chunk
+    }
+
+    // representants on the same path are there at the same time
+    // so they need chunks of their own
+    pub fn split(representants: Vec<(Path, Representant)>) -> Vec<CollectionChunk> {
+        let mut chunks: Vec<CollectionChunk> = Vec::new();
+
+        for (path, repr) in representants {
+            match chunks.iter().position(|chunk| !chunk.representants.contains_key(&path)) {
+                Some(index) => chunks[index].add_representant(path, repr),
+                None => {
+                    let mut new_chunk = CollectionChunk::empty();
+                    new_chunk.add_representant(path, repr);
+                    chunks.push(new_chunk);
+                }
+            }
+        }
+
+        chunks
+    }
+
     #[allow(should_implement_trait)]
     pub fn into_iter(self) -> btree_map::IntoIter<Path, Representant> {
         self.representants.into_iter()
@@ -140,6 +170,47 @@ impl CollectionBranch {
         self.content.insert(0, new_chunk);
     }
 
+    // the first or last element can be taken off exactly
+    // otherwise it's unknown which element is gone, any of them could be
+    pub fn remove(&mut self, index: Option<i16>) {
+        let position = match index {
+            Some(0) if !self.content.is_empty() => Some(0),
+            Some(-1) if !self.content.is_empty() => Some(self.content.len() - 1),
+            _ => None,
+        };
+
+        self.min_size = self.min_size.map(|old| old.saturating_sub(1));
+        self.max_size = self.max_size.map(|old| old.saturating_sub(1));
+
+        if let Some(position) = position {
+            let exact = {
+                let chunk = &self.content[position];
+                chunk.min_size == Some(1) && chunk.max_size == Some(1)
+            };
+
+            if exact {
+                self.content.remove(position);
+                return;
+            }
+        }
+
+        self.make_optional();
+    }
+
+    // any number of elements could be gone
+    pub fn remove_all(&mut self) {
+        self.min_size = Some(0);
+        self.make_optional();
+    }
+
+    fn make_optional(&mut self) {
+        self.content = self.content.iter().map(|chunk| chunk.optional()).collect();
+    }
+
+    pub fn reverse(&mut self) {
+        self.content.reverse();
+    }
+
     fn first_combinations(&self, n: i16) -> Vec<VecDeque<Mapping>> {
         let result = linearize(n as usize, &self.content, false);
         
@@ -243,7 +314,9 @@ impl CollectionBranch {
         result
     }
 
-    pub fn slice(&self, start: i16, end: i16) -> CollectionBranch {
+    // drops the first start and the last end elements, then takes a step
+    // an unknown step is None
+    pub fn slice(&self, start: i16, end: i16, step: Option<i16>) -> CollectionBranch {
         let mut min_counts: BTreeMap<Mapping, usize> = BTreeMap::new();
         let mut max_counts: BTreeMap<Mapping, usize> = BTreeMap::new();
 
@@ -336,7 +409,49 @@ impl CollectionBranch {
             }
         }
 
-        CollectionBranch::new(new_content)
+        let mut result = CollectionBranch::new(new_content);
+
+        // negative steps go from back to front
+        if step.map_or(false, |n| n < 0) {
+            result.reverse();
+        }
+
+        match step.map(|n| n.abs() as usize) {
+            Some(1) => result,
+            step => result.every(step),
+        }
+    }
+
+    // keeps every nth element, which ones is only known for single elements
+    // an unknown step keeps at least one element
+    fn every(&self, step: Option<usize>) -> CollectionBranch {
+        // the positions of single elements are known
+        if let Some(n) = step {
+            if self.content.iter().all(|chunk| chunk.min_size == Some(1) && chunk.max_size == Some(1)) {
+                return CollectionBranch::new(self.content.iter().cloned().step_by(n).collect());
+            }
+        }
+
+        let (min, max) = match step {
+            Some(n) => (self.min_size.map(|m| (m + n - 1) / n), self.max_size.map(|m| (m + n - 1) / n)),
+            None => (self.min_size.map(|m| cmp::min(m, 1)), self.max_size),
+        };
+
+        let count: usize = self.content.iter().map(|chunk| chunk.len()).sum();
+
+        // the minimum is only kept when the type is known
+        let min = if count == 1 { min } else { Some(0) };
+
+        let representants = self.content.iter()
+            .flat_map(|chunk| chunk.iter())
+            .map(|(path, repr)| (path.clone(), Representant::new(repr.object, repr.kind, min, max)))
+            .collect();
+
+        // every representant covers the whole range, the chunks don't add up
+        let mut result = CollectionBranch::new(CollectionChunk::split(representants));
+        result.min_size = min;
+        result.max_size = max;
+        result
     }
 
     pub fn concatenate(&mut self, other: CollectionBranch) {
@@ -450,6 +565,27 @@ impl Branch {
         }
     }
 
+    pub fn remove(&mut self, index: Option<i16>) {
+        for mapping in &mut self.content {
+            let &mut CollectionMapping {ref mut branch, ..} = mapping;
+            branch.remove(index);
+        }
+    }
+
+    pub fn remove_all(&mut self) {
+        for mapping in &mut self.content {
+            let &mut CollectionMapping {ref mut branch, ..} = mapping;
+            branch.remove_all();
+        }
+    }
+
+    pub fn reverse(&mut self) {
+        for mapping in &mut self.content {
+            let &mut CollectionMapping {ref mut branch, ..} = mapping;
+            branch.reverse();
+        }
+    }
+
     #[allow(ptr_arg)]
     pub fn get_element(&self, n: i16, node: &PathID) -> Mapping {
         let mut result = Mapping::new();
@@ -588,11 +724,11 @@ impl Branch {
         result
     }
 
-    pub fn slice(&self, start: i16, end: i16) -> Vec<(Path, CollectionBranch)> {
+    pub fn slice(&self, start: i16, end: i16, step: Option<i16>) -> Vec<(Path, CollectionBranch)> {
         let mut result = Vec::new();
         for coll_mapping in &self.content {
             let &CollectionMapping {ref path, ref branch} = coll_mapping;
-            let new_branch = branch.slice(start, end);
+            let new_branch = branch.slice(start, end, step);
             result.push( (path.clone(), new_branch));
         }
 
@@ -679,6 +815,18 @@ impl Frame {
         self.branches[self.current].prepend(element)
     }
 
+    pub fn remove(&mut self, index: Option<i16>) {
+        self.branches[self.current].remove(index)
+    }
+
+    pub fn remove_all(&mut self) {
+        self.branches[self.current].remove_all()
+    }
+
+    pub fn reverse(&mut self) {
+        self.branches[self.current].reverse()
+    }
+
     #[allow(ptr_arg)]
     pub fn get_element(&self, n: i16, node: &PathID) -> Mapping {
         self.branches[self.current].get_element(n, node)
@@ -703,8 +851,8 @@ impl Frame {
         self.branches[self.current].get_last_n(n, node)
     }
 
-    pub fn slice(&self, start: i16, end: i16) -> Vec<(Path, CollectionBranch)> {
-        self.branches[self.current].slice(start, end)
+    pub fn slice(&self, start: i16, end: i16, step: Option<i16>) -> Vec<(Path, CollectionBranch)> {
+        self.branches[self.current].slice(start, end, step)
     }
 }
 
@@ -937,9 +1085,9 @@ impl Collection {
         }
     }
 
-    pub fn slice(&self, start: i16, end: i16) -> Vec<(Path, CollectionBranch)> {
+    pub fn slice(&self, start: i16, end: i16, step: Option<i16>) -> Vec<(Path, CollectionBranch)> {
         if let Some(frame) = self.frames.last() {
-            frame.slice(start, end)
+            frame.slice(start, end, step)
         } else {
             panic!("No frames in this collection")
         }
@@ -976,6 +1124,30 @@ impl Collection {
             panic!("No frames in this collection")
         }
     }
+
+    pub fn remove(&mut self, index: Option<i16>, path: Path) {
+        if let Some(frame) = self.current_frame_mut(path) {
+            frame.remove(index)
+        } else {
+            panic!("No frames in this collection")
+        }
+    }
+
+    pub fn remove_all(&mut self, path: Path) {
+        if let Some(frame) = self.current_frame_mut(path) {
+            frame.remove_all()
+        } else {
+            panic!("No frames in this collection")
+        }
+    }
+
+    pub fn reverse(&mut self, path: Path) {
+        if let Some(frame) = self.current_frame_mut(path) {
+            frame.reverse()
+        } else {
+            panic!("No frames in this collection")
+        }
+    }
 }
 
 fn linearize(n: usize,
@@ -1057,3 +1229,98 @@ fn linearize(n: usize,
         _ => panic!("not enough elements to unpack"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a collection of single elements, every object is its own type
+    fn branch(objects: &[Pointer]) -> CollectionBranch {
+        let content = objects.iter()
+            .map(|object| {
+                let mut chunk = CollectionChunk::empty();
+                chunk.add_representant(Path::empty(), Representant::new(*object, *object, Some(1), Some(1)));
+                chunk
+            })
+            .collect();
+
+        CollectionBranch::new(content)
+    }
+
+    fn objects(mapping: &Mapping) -> Vec<Pointer> {
+        mapping.into_iter().map(|&(_, address)| address).collect()
+    }
+
+    #[test]
+    fn remove_first_and_last() {
+        let mut collection = branch(&[1, 2, 3]);
+
+        collection.remove(Some(0));
+        assert_eq!(collection.size_range(), (Some(2), Some(2)));
+        assert_eq!(objects(&collection.get_element(1)), vec![2]);
+
+        collection.remove(Some(-1));
+        assert_eq!(collection.size_range(), (Some(1), Some(1)));
+        assert_eq!(objects(&collection.get_element(-1)), vec![2]);
+    }
+
+    #[test]
+    fn remove_unknown_element() {
+        let mut collection = branch(&[1, 2, 3]);
+        collection.remove(Some(1));
+
+        // every element might be the removed one
+        assert_eq!(collection.size_range(), (Some(2), Some(2)));
+        assert!(collection.content.iter().all(|chunk| chunk.min_size == Some(0)));
+    }
+
+    #[test]
+    fn remove_from_empty() {
+        let mut collection = branch(&[]);
+        collection.remove(Some(0));
+
+        assert_eq!(collection.size_range(), (Some(0), Some(0)));
+    }
+
+    #[test]
+    fn remove_all() {
+        let mut collection = branch(&[1, 2, 3]);
+        collection.remove_all();
+
+        assert_eq!(collection.size_range(), (Some(0), Some(3)));
+    }
+
+    #[test]
+    fn slice_drops_both_ends() {
+        let collection = branch(&[1, 2, 3, 4]).slice(1, 1, Some(1));
+
+        assert_eq!(collection.size_range(), (Some(2), Some(2)));
+        assert_eq!(objects(&collection.get_element(1)), vec![2]);
+        assert_eq!(objects(&collection.get_element(2)), vec![3]);
+    }
+
+    #[test]
+    fn slice_with_step() {
+        let collection = branch(&[1, 2, 3, 4]).slice(0, 0, Some(2));
+
+        assert_eq!(collection.size_range(), (Some(2), Some(2)));
+        assert_eq!(objects(&collection.get_element(1)), vec![1]);
+        assert_eq!(objects(&collection.get_element(2)), vec![3]);
+    }
+
+    #[test]
+    fn slice_with_negative_step() {
+        let collection = branch(&[1, 2, 3]).slice(0, 0, Some(-1));
+
+        assert_eq!(collection.size_range(), (Some(3), Some(3)));
+        assert_eq!(objects(&collection.get_element(1)), vec![3]);
+        assert_eq!(objects(&collection.get_element(-1)), vec![1]);
+    }
+
+    #[test]
+    fn slice_with_unknown_step() {
+        let collection = branch(&[1, 2, 3]).slice(0, 0, None);
+
+        assert_eq!(collection.size_range(), (Some(0), Some(3)));
+    }
+}
@@ -0,0 +1,287 @@
+use toml;
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::prelude::*;
+
+use super::Severity;
+use super::Baseline;
+use super::RULES;
+
+// settings from fosite.toml
+//
+// [rules]
+// disable = ["HETERO_COLLECTION"]
+//
+// [rules.TYPE_UNSAFE]
+// severity = "error"
+//
+// [limits]
+// messages = 100
+// per_rule = 10
+//
+// [baseline]
+// file = "fosite-baseline.json"
+pub struct Config {
+    disabled: Vec<i16>,
+    severities: HashMap<i16, Severity>,
+    max_messages: Option<usize>,
+    max_per_rule: Option<usize>,
+    baseline_file: Option<String>,
+
+    // set from the command line
+    baseline: Option<Baseline>,
+    update_baseline: Option<String>,
+}
+
+impl Config {
+    pub fn new() -> Config {
+        Config {
+            disabled: Vec::new(),
+            severities: HashMap::new(),
+            max_messages: None,
+            max_per_rule: None,
+            baseline_file: None,
+            baseline: None,
+            update_baseline: None,
+        }
+    }
+
+    // a missing file is not an error, the defaults are used
+    pub fn load(path: &str) -> Result<Config, String> {
+        let mut text = String::new();
+
+        match File::open(path) {
+            Ok(mut file) => {
+                if let Err(why) = file.read_to_string(&mut text) {
+                    return Err(format!("{}: {}", path, why));
+                }
+            }
+            Err(_) => return Ok(Config::new()),
+        }
+
+        Config::parse(&text).map_err(|why| format!("{}: {}", path, why))
+    }
+
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let mut parser = toml::Parser::new(text);
+
+        let table = match parser.parse() {
+            Some(table) => table,
+            None => {
+                let error = &parser.errors[0];
+                let (line, col) = parser.to_linecol(error.lo);
+                return Err(format!("{} at row {}, column {}", error.desc, line + 1, col + 1));
+            }
+        };
+
+        let mut config = Config::new();
+
+        if let Some(rules) = table.get("rules").and_then(|rules| rules.as_table()) {
+            for (key, value) in rules {
+                if key == "disable" || key == "enable" {
+                    let names = match value.as_slice() {
+                        Some(names) => names,
+                        None => return Err(format!("rules.{} should be a list of rules", key)),
+                    };
+
+                    for name in names {
+                        let code = rule_code(name.as_str().unwrap_or(""))?;
+                        config.disabled.retain(|c| *c != code);
+
+                        if key == "disable" {
+                            config.disabled.push(code);
+                        }
+                    }
+
+                    continue;
+                }
+
+                let code = rule_code(key)?;
+                let rule = match value.as_table() {
+                    Some(rule) => rule,
+                    None => return Err(format!("rules.{} should be a table", key)),
+                };
+
+                if let Some(enabled) = rule.get("enabled").and_then(|e| e.as_bool()) {
+                    config.disabled.retain(|c| *c != code);
+
+                    if !enabled {
+                        config.disabled.push(code);
+                    }
+                }
+
+                if let Some(severity) = rule.get("severity") {
+                    let severity = match severity.as_str() {
+                        Some("warning") => Severity::Warning,
+                        Some("error") => Severity::Error,
+                        _ => return Err(format!("rules.{}.severity should be \"warning\" or \"error\"", key)),
+                    };
+
+                    config.severities.insert(code, severity);
+                }
+            }
+        }
+
+        if let Some(limits) = table.get("limits").and_then(|limits| limits.as_table()) {
+            config.max_messages = limit(limits.get("messages"), "messages")?;
+            config.max_per_rule = limit(limits.get("per_rule"), "per_rule")?;
+        }
+
+        if let Some(baseline) = table.get("baseline").and_then(|baseline| baseline.as_table()) {
+            if let Some(file) = baseline.get("file") {
+                match file.as_str() {
+                    Some(file) => config.baseline_file = Some(file.to_owned()),
+                    None => return Err("baseline.file should be a path".to_owned()),
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn is_enabled(&self, code: i16) -> bool {
+        !self.disabled.contains(&code)
+    }
+
+    pub fn severity(&self, code: i16) -> Option<Severity> {
+        self.severities.get(&code).cloned()
+    }
+
+    pub fn severities(&self) -> &HashMap<i16, Severity> {
+        &self.severities
+    }
+
+    pub fn max_messages(&self) -> Option<usize> {
+        self.max_messages
+    }
+
+    pub fn max_per_rule(&self) -> Option<usize> {
+        self.max_per_rule
+    }
+
+    pub fn baseline_file(&self) -> Option<&String> {
+        self.baseline_file.as_ref()
+    }
+
+    // messages in the baseline aren't reported
+    pub fn set_baseline(&mut self, baseline: Baseline) {
+        self.baseline = Some(baseline);
+    }
+
+    pub fn take_baseline(&mut self) -> Option<Baseline> {
+        self.baseline.take()
+    }
+
+    // write the reported messages to a new baseline instead
+    pub fn set_update_baseline(&mut self, path: String) {
+        self.update_baseline = Some(path);
+    }
+
+    pub fn update_baseline(&self) -> Option<&String> {
+        self.update_baseline.as_ref()
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn rule_code(name: &str) -> Result<i16, String> {
+    match RULES.iter().find(|&&(_, rule, _)| rule == name) {
+        Some(&(code, _, _)) => Ok(code),
+        None => Err(format!("unknown rule {}", name)),
+    }
+}
+
+fn limit(value: Option<&toml::Value>, name: &str) -> Result<Option<usize>, String> {
+    match value {
+        None => Ok(None),
+        Some(value) => match value.as_integer() {
+            Some(n) if n >= 0 => Ok(Some(n as usize)),
+            _ => Err(format!("limits.{} should be a positive number", name)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{HETERO_COLLECTION, OUT_OF_BOUNDS, TYPE_UNSAFE};
+
+    #[test]
+    fn empty_config_uses_the_defaults() {
+        let config = Config::parse("").unwrap();
+
+        assert!(config.is_enabled(HETERO_COLLECTION));
+        assert_eq!(config.severity(TYPE_UNSAFE), None);
+        assert_eq!(config.max_messages(), None);
+        assert_eq!(config.max_per_rule(), None);
+        assert_eq!(config.baseline_file(), None);
+    }
+
+    #[test]
+    fn missing_file_uses_the_defaults() {
+        let config = Config::load("/nonexistent/fosite.toml").unwrap();
+
+        assert!(config.is_enabled(HETERO_COLLECTION));
+    }
+
+    #[test]
+    fn rules_can_be_disabled_and_enabled() {
+        let config = Config::parse(r#"
+            [rules]
+            disable = ["HETERO_COLLECTION", "OUT_OF_BOUNDS"]
+            enable = ["OUT_OF_BOUNDS"]
+
+            [rules.TYPE_UNSAFE]
+            enabled = false
+        "#).unwrap();
+
+        assert!(!config.is_enabled(HETERO_COLLECTION));
+        assert!(config.is_enabled(OUT_OF_BOUNDS));
+        assert!(!config.is_enabled(TYPE_UNSAFE));
+    }
+
+    #[test]
+    fn severities_limits_and_baseline() {
+        let config = Config::parse(r#"
+            [rules.TYPE_UNSAFE]
+            severity = "error"
+
+            [limits]
+            messages = 100
+            per_rule = 10
+
+            [baseline]
+            file = "old.json"
+        "#).unwrap();
+
+        assert_eq!(config.severity(TYPE_UNSAFE), Some(Severity::Error));
+        assert_eq!(config.max_messages(), Some(100));
+        assert_eq!(config.max_per_rule(), Some(10));
+        assert_eq!(config.baseline_file(), Some(&"old.json".to_owned()));
+    }
+
+    #[test]
+    fn invalid_settings_are_errors() {
+        let error = |text| Config::parse(text).err().unwrap();
+
+        assert_eq!(error("[rules]\ndisable = [\"NOT_A_RULE\"]"), "unknown rule NOT_A_RULE");
+        assert_eq!(error("[rules]\ndisable = \"HETERO_COLLECTION\""),
+                   "rules.disable should be a list of rules");
+        assert_eq!(error("[rules.TYPE_UNSAFE]\nseverity = \"fatal\""),
+                   "rules.TYPE_UNSAFE.severity should be \"warning\" or \"error\"");
+        assert_eq!(error("[limits]\nmessages = -1"), "limits.messages should be a positive number");
+        assert_eq!(error("[baseline]\nfile = 3"), "baseline.file should be a path");
+    }
+
+    #[test]
+    fn syntax_errors_have_a_position() {
+        let error = Config::parse("[rules]\ndisable = [").err().unwrap();
+
+        assert!(error.contains("at row 2"), "{}", error);
+    }
+}
@@ -0,0 +1,87 @@
+use rustc_serialize::json::Json;
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use super::GastID;
+use super::GastNode;
+use super::PathID;
+use super::MessageContent;
+use super::Sources;
+use super::RULES;
+use super::rule_index;
+use super::rule_severity;
+use super::Severity;
+use super::describe_node;
+
+type Nodes = HashMap<GastID, GastNode>;
+
+// converts a message into a Language Server Protocol diagnostic
+pub fn lsp_diagnostic(content: &MessageContent, uri: &str, sources: &Sources, nodes: &Nodes, node: &PathID) -> Option<Json> {
+    let text = match content.summary(nodes, node) {
+        Some(text) => text,
+        None => return None,
+    };
+
+    let &(row, col) = match node.last().and_then(|source| sources.get(source)) {
+        Some(position) => position,
+        None => return None,
+    };
+
+    let code = content.code();
+    let severity = match sources.severity(code, rule_severity(code)) {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+    };
+
+    let mut related = Vec::new();
+
+    for path in content.paths(sources) {
+        for path_node in &path {
+            if let Some(&(row, col)) = path_node.get_location().last().and_then(|source| sources.get(source)) {
+                let mut location = BTreeMap::new();
+                location.insert("uri".to_owned(), Json::String(uri.to_owned()));
+                let end = sources.get_end(path_node.get_location().last().unwrap()).cloned();
+                location.insert("range".to_owned(), lsp_range((row, col), end.unwrap_or((row, col))));
+
+                let mut information = BTreeMap::new();
+                information.insert("location".to_owned(), Json::Object(location));
+                information.insert("message".to_owned(), Json::String(describe_node(path_node)));
+                related.push(Json::Object(information));
+            }
+        }
+    }
+
+    let mut diagnostic = BTreeMap::new();
+    let end = sources.get_end(node.last().unwrap()).cloned();
+    diagnostic.insert("range".to_owned(), lsp_range((row, col), end.unwrap_or((row, col))));
+    diagnostic.insert("severity".to_owned(), Json::I64(severity));
+    diagnostic.insert("source".to_owned(), Json::String("fosite".to_owned()));
+    diagnostic.insert("message".to_owned(), Json::String(text));
+
+    if let Some(index) = rule_index(code) {
+        let (_, name, _) = RULES[index];
+        diagnostic.insert("code".to_owned(), Json::String(name.to_owned()));
+    }
+
+    if !related.is_empty() {
+        diagnostic.insert("relatedInformation".to_owned(), Json::Array(related));
+    }
+
+    Some(Json::Object(diagnostic))
+}
+
+// rows start at 1 in the sources, lines start at 0 in the protocol
+pub fn lsp_range(start: (i16, i16), end: (i16, i16)) -> Json {
+    let mut range = BTreeMap::new();
+    range.insert("start".to_owned(), lsp_position(start));
+    range.insert("end".to_owned(), lsp_position(end));
+    Json::Object(range)
+}
+
+fn lsp_position((row, col): (i16, i16)) -> Json {
+    let mut position = BTreeMap::new();
+    position.insert("line".to_owned(), Json::I64(row as i64 - 1));
+    position.insert("character".to_owned(), Json::I64(col as i64));
+    Json::Object(position)
+}
@@ -9,11 +9,13 @@ pub struct Executors {
     pub binop: Option<Box<BinOpExecutor>>,
     pub boolop: Option<Box<BoolOpExecutor>>,
     pub conditional: Option<Box<ConditionalExecutor>>,
+    pub conditional_expression: Option<Box<ConditionalExpressionExecutor>>,
     pub block: Option<Box<BlockExecutor>>,
     pub identifier: Option<Box<IdentifierExecutor>>,
     pub attribute: Option<Box<AttributeExecutor>>,
     pub boolean: Option<Box<BooleanExecutor>>,
     pub string: Option<Box<StringExecutor>>,
+    pub joined_string: Option<Box<JoinedStrExecutor>>,
     pub int: Option<Box<IntExecutor>>,
     pub float: Option<Box<FloatExecutor>>,
     pub declaration: Option<Box<DeclarationExecutor>>,
@@ -21,6 +23,9 @@ pub struct Executors {
     pub while_loop: Option<Box<WhileExecutor>>,
     pub break_loop: Option<Box<BreakExecutor>>,
     pub continue_loop: Option<Box<ContinueExecutor>>,
+    pub global: Option<Box<GlobalExecutor>>,
+    pub nonlocal: Option<Box<NonlocalExecutor>>,
+    pub delete: Option<Box<DeleteExecutor>>,
     pub list: Option<Box<ListExecutor>>,
     pub sequence: Option<Box<SequenceExecutor>>,
     pub index: Option<Box<IndexExecutor>>,
@@ -54,7 +59,8 @@ pub trait SliceExecutor {
                env: Environment,
                target: &GastNode,
                lower: &GastNode,
-               upper: &GastNode)
+               upper: &GastNode,
+               step: &GastNode)
                -> ExecutionResult;
 }
 
@@ -62,7 +68,8 @@ pub trait AssignExecutor {
     fn execute(&self,
                env: Environment,
                targets: &[GastNode],
-               value: &GastNode)
+               value: &GastNode,
+               augmented: bool)
                -> ExecutionResult;
 
     fn direct(&self,
@@ -70,6 +77,13 @@ pub trait AssignExecutor {
                target: String,
                value: Mapping)
                -> ExecutionResult;
+
+    // for targets that aren't part of an assignment, like those of a for loop
+    fn to_target(&self,
+               env: Environment,
+               target: &GastNode,
+               value: Mapping)
+               -> ExecutionResult;
 }
 
 pub trait UnOpExecutor {
@@ -123,10 +137,26 @@ pub trait ContinueExecutor {
     fn execute(&self, env: Environment) -> ExecutionResult;
 }
 
+pub trait GlobalExecutor {
+    fn execute(&self, env: Environment, names: &[String]) -> ExecutionResult;
+}
+
+pub trait NonlocalExecutor {
+    fn execute(&self, env: Environment, names: &[String]) -> ExecutionResult;
+}
+
+pub trait DeleteExecutor {
+    fn execute(&self, env: Environment, targets: &[GastNode]) -> ExecutionResult;
+}
+
 pub trait StringExecutor {
     fn execute(&self, env: Environment) -> ExecutionResult;
 }
 
+pub trait JoinedStrExecutor {
+    fn execute(&self, env: Environment, values: &[GastNode]) -> ExecutionResult;
+}
+
 pub trait BooleanExecutor {
     fn execute(&self, env: Environment, value: bool) -> ExecutionResult;
 }
@@ -186,11 +216,21 @@ pub trait ConditionalExecutor {
                -> ExecutionResult;
 }
 
+pub trait ConditionalExpressionExecutor {
+    fn execute(&self,
+               env: Environment,
+               test: &GastNode,
+               body: &GastNode,
+               or_else: &GastNode)
+               -> ExecutionResult;
+}
+
 pub trait WhileExecutor {
     fn execute(&self,
                env: Environment,
                test: &GastNode,
-               body: &GastNode)
+               body: &GastNode,
+               or_else: &GastNode)
                -> ExecutionResult;
 }
 
@@ -198,7 +238,8 @@ pub trait ForEachExecutor {
     fn execute(&self,
                env: Environment,
                before: &GastNode,
-               body: &GastNode)
+               body: &GastNode,
+               or_else: &GastNode)
                -> ExecutionResult;
 }
 
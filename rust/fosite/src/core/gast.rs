@@ -36,10 +36,12 @@ pub enum NodeType {
     Assignment {
         targets: Vec<GastNode>,
         value: Box<GastNode>,
+        augmented: bool,
     },
     Int { value: i64 },
     Float { value: f64 },
     String { value: String },
+    JoinedStr { values: Vec<GastNode> },
     List { content: Vec<GastNode> },
     Set { content: Vec<GastNode> },
     Dict { content: Vec<GastNode> },
@@ -51,13 +53,20 @@ pub enum NodeType {
         body: Box<GastNode>,
         or_else: Box<GastNode>,
     },
+    IfExp {
+        test: Box<GastNode>,
+        body: Box<GastNode>,
+        or_else: Box<GastNode>,
+    },
     While {
         test: Box<GastNode>,
         body: Box<GastNode>,
+        or_else: Box<GastNode>,
     },
     ForEach {
         before: Box<GastNode>,
         body: Box<GastNode>,
+        or_else: Box<GastNode>,
     },
     BinOp {
         left: Box<GastNode>,
@@ -74,6 +83,9 @@ pub enum NodeType {
     },
     Break { },
     Continue { },
+    Global { names: Vec<String> },
+    Nonlocal { names: Vec<String> },
+    Delete { targets: Vec<GastNode> },
     Boolean { value: bool },
     Nil {},
     UnOp { op: String, value: Box<GastNode> },
@@ -93,6 +105,11 @@ pub enum NodeType {
         source: Box<GastNode>,
         op: Box<GastNode>,
     },
+    MapPair {
+        source: Box<GastNode>,
+        key: Box<GastNode>,
+        value: Box<GastNode>,
+    },
     AndThen {
         first: Box<GastNode>,
         second: Box<GastNode>,
@@ -114,6 +131,7 @@ pub enum NodeType {
         target: Box<GastNode>,
         lower: Box<GastNode>,
         upper: Box<GastNode>,
+        step: Box<GastNode>,
     },
     Argument {
         name: String,
@@ -185,6 +203,27 @@ pub fn build(node: &Json) -> GastNode {
 
     let line = obj.get("line");
     let col = obj.get("col");
+    let end_line = obj.get("end_line");
+    let end_col = obj.get("end_col");
+
+    // statements preceded by a suppression comment
+    if let Some(codes) = obj.get("ignore").and_then(|codes| codes.as_array()) {
+        let codes = codes.iter()
+            .filter_map(|code| code.as_string())
+            .map(|code| code.to_owned())
+            .collect();
+
+        CHANNEL.publish(Message::Suppress {
+            source: id,
+            codes: codes,
+            last_row: last_row(node),
+        });
+    }
+
+    // only the root block holds the source text
+    if let Some(text) = obj.get("source").and_then(|text| text.as_string()) {
+        CHANNEL.publish(Message::Source { text: text.to_owned() });
+    }
 
     let node = match kind {
         "block" => build_block(id, obj.get("content").unwrap()),
@@ -193,10 +232,12 @@ pub fn build(node: &Json) -> GastNode {
         "int" => build_int(id, node),
         "float" => build_float(id, node),
         "string" => build_string(id, node),
+        "joinedstr" => build_joined_string(id, node),
         "attribute" => build_attribute(id, node),
         "list" => build_list(id, node),
         "sequence" => build_sequence(id, node),
         "if" => build_if(id, node),
+        "ifexp" => build_ifexp(id, node),
         "while" => build_while(id, node),
         "foreach" => build_foreach(id, node),
         "binop" => build_binop(id, node),
@@ -205,6 +246,9 @@ pub fn build(node: &Json) -> GastNode {
         "boolop" => build_boolop(id, node),
         "break" => build_break(id),
         "continue" => build_continue(id),
+        "global" => build_global(id, node),
+        "nonlocal" => build_nonlocal(id, node),
+        "delete" => build_delete(id, node),
         "unop" => build_unop(id, node),
         "index" => build_index(id, node),
         "set" => build_set(id, node),
@@ -213,6 +257,7 @@ pub fn build(node: &Json) -> GastNode {
         "generator" => build_generator(id, node),
         "filter" => build_filter(id, node),
         "map" => build_map(id, node),
+        "mappair" => build_mappair(id, node),
         "andthen" => build_andthen(id, node),
         "call" => build_call(id, node),
         "import" => build_import(id, node),
@@ -225,10 +270,18 @@ pub fn build(node: &Json) -> GastNode {
     };
 
     if let (Some(line), Some(col)) = (line, col) {
+        let end = match (end_line, end_col) {
+            (Some(end_line), Some(end_col)) => {
+                Some((end_line.as_i64().unwrap() as i16, end_col.as_i64().unwrap() as i16))
+            }
+            _ => None,
+        };
+
         let message = Message::Input {
             source: id,
             line: line.as_i64().unwrap() as i16,
             col: col.as_i64().unwrap() as i16,
+            end: end,
             node: node.clone(),
         };
 
@@ -238,6 +291,49 @@ pub fn build(node: &Json) -> GastNode {
     node
 }
 
+// the last row of a node or any of its children
+fn last_row(node: &Json) -> Option<i16> {
+    match *node {
+        Json::Object(ref obj) => {
+            let own = obj.get("end_line")
+                .or_else(|| obj.get("line"))
+                .and_then(|line| line.as_i64())
+                .map(|line| line as i16);
+
+            obj.values().filter_map(last_row).chain(own).max()
+        }
+        Json::Array(ref content) => content.iter().filter_map(last_row).max(),
+        _ => None,
+    }
+}
+
+fn build_names(node: &Json) -> Vec<String> {
+    let obj = node.as_object().unwrap();
+    let json_names = obj.get("names").unwrap();
+
+    json_names.as_array().unwrap()
+        .iter()
+        .map(|name| name.as_string().unwrap().to_owned())
+        .collect()
+}
+
+fn build_global(id: GastID, node: &Json) -> GastNode {
+    GastNode::new(id, NodeType::Global { names: build_names(node) })
+}
+
+fn build_nonlocal(id: GastID, node: &Json) -> GastNode {
+    GastNode::new(id, NodeType::Nonlocal { names: build_names(node) })
+}
+
+fn build_delete(id: GastID, node: &Json) -> GastNode {
+    let obj = node.as_object().unwrap();
+    let json_targets = obj.get("targets").unwrap().as_array().unwrap();
+
+    let targets = json_targets.iter().map(build).collect();
+
+    GastNode::new(id, NodeType::Delete { targets })
+}
+
 fn build_import(id: GastID, node: &Json) -> GastNode {
     let obj = node.as_object().unwrap();
 
@@ -310,8 +406,9 @@ fn build_return(id: GastID, node: &Json) -> GastNode {
 fn build_argument(id: GastID, node: &Json) -> GastNode {
     let obj = node.as_object().unwrap();
 
+    // unpacked dicts don't have a name
     let json_name = obj.get("name").unwrap();
-    let name = json_name.as_string().unwrap().to_owned();
+    let name = json_name.as_string().unwrap_or("**").to_owned();
 
     let json_value = obj.get("value").unwrap();
     let value = Box::new(build(json_value));
@@ -335,11 +432,15 @@ fn build_slice(id: GastID, node: &Json) -> GastNode {
     let json_upper = obj.get("upper").unwrap();
     let upper = Box::new(build(json_upper));
 
+    let json_step = obj.get("step").unwrap();
+    let step = Box::new(build(json_step));
+
     GastNode::new(id,
                          NodeType::Slice {
                              target: target,
                              lower: lower,
                              upper: upper,
+                             step: step,
                          })
 }
 
@@ -464,6 +565,26 @@ fn build_if(id: GastID, node: &Json) -> GastNode {
                          })
 }
 
+fn build_ifexp(id: GastID, node: &Json) -> GastNode {
+    let obj = node.as_object().unwrap();
+
+    let json_test = obj.get("test").unwrap();
+    let test = Box::new(build(json_test));
+
+    let json_body = obj.get("body").unwrap();
+    let body = Box::new(build(json_body));
+
+    let json_orelse = obj.get("orElse").unwrap();
+    let or_else = Box::new(build(json_orelse));
+
+    GastNode::new(id,
+                         NodeType::IfExp {
+                             test: test,
+                             body: body,
+                             or_else: or_else,
+                         })
+}
+
 fn build_function(id: GastID, node: &Json) -> GastNode {
     let obj = node.as_object().unwrap();
 
@@ -520,11 +641,14 @@ fn build_while(id: GastID, node: &Json) -> GastNode {
     let json_body = obj.get("body").unwrap();
     let body = Box::new(build(json_body));
 
+    let json_orelse = obj.get("orElse").unwrap();
+    let or_else = Box::new(build(json_orelse));
 
     GastNode::new(id,
                          NodeType::While {
                              test: test,
                              body: body,
+                             or_else: or_else,
                          })
 }
 
@@ -537,10 +661,14 @@ fn build_foreach(id: GastID, node: &Json) -> GastNode {
     let json_body = obj.get("body").unwrap();
     let body = Box::new(build(json_body));
 
+    let json_orelse = obj.get("orElse").unwrap();
+    let or_else = Box::new(build(json_orelse));
+
     GastNode::new(id,
                          NodeType::ForEach {
                              before: before,
                              body: body,
+                             or_else: or_else,
                          })
 }
 
@@ -567,10 +695,14 @@ fn build_assign(id: GastID, node: &Json) -> GastNode {
     let json_value = obj.get("value").unwrap();
     let value = Box::new(build(json_value));
 
+    let json_augmented = obj.get("augmented").unwrap();
+    let augmented = json_augmented.as_boolean().unwrap();
+
     GastNode::new(id,
                          NodeType::Assignment {
                              targets: targets,
                              value: value,
+                             augmented: augmented,
                          })
 }
 
@@ -598,6 +730,18 @@ fn build_string(id: GastID, node: &Json) -> GastNode {
     GastNode::new(id, NodeType::String { value: value })
 }
 
+fn build_joined_string(id: GastID, node: &Json) -> GastNode {
+    let obj = node.as_object().unwrap();
+    let array = obj.get("values").unwrap().as_array().unwrap();
+    let mut values = Vec::new();
+
+    for value in array {
+        values.push(build(value));
+    }
+
+    GastNode::new(id, NodeType::JoinedStr { values: values })
+}
+
 fn build_attribute(id: GastID, node: &Json) -> GastNode {
     let obj = node.as_object().unwrap();
     let raw_parent = obj.get("of").unwrap();
@@ -729,6 +873,21 @@ fn build_map(id: GastID, node: &Json) -> GastNode {
     GastNode::new(id, NodeType::Map { source: source, op: op })
 }
 
+fn build_mappair(id: GastID, node: &Json) -> GastNode {
+    let obj = node.as_object().unwrap();
+
+    let json_source = obj.get("source").unwrap();
+    let source = Box::new(build(json_source));
+
+    let json_key = obj.get("key").unwrap();
+    let key = Box::new(build(json_key));
+
+    let json_value = obj.get("value").unwrap();
+    let value = Box::new(build(json_value));
+
+    GastNode::new(id, NodeType::MapPair { source: source, key: key, value: value })
+}
+
 fn build_andthen(id: GastID, node: &Json) -> GastNode {
     let obj = node.as_object().unwrap();
     
@@ -11,6 +11,8 @@ pub struct KnowledgeBase {
     indexable_types: BidirMap<String, Pointer>,
     constants: HashMap<String, Pointer>,
     arithmetic_types: HashMap<String, HashSet<String>>,
+    operations: HashMap<(String, String, String), String>,
+    reflected_operations: HashMap<(String, String, String), String>,
 }
 
 impl KnowledgeBase {
@@ -22,6 +24,8 @@ impl KnowledgeBase {
             iterable_types: BidirMap::new(),
             indexable_types: BidirMap::new(),
             arithmetic_types: HashMap::new(),
+            operations: HashMap::new(),
+            reflected_operations: HashMap::new(),
         }
     }
 
@@ -39,6 +43,28 @@ impl KnowledgeBase {
         }
     }
 
+    // `left op right` has type `result`, like __add__
+    pub fn add_operation(&mut self, left: &str, op: &str, right: &str, result: &str) {
+        let key = (left.to_owned(), op.to_owned(), right.to_owned());
+        self.operations.insert(key, result.to_owned());
+    }
+
+    // `left op right` is handled by the right side, like __radd__
+    pub fn add_reflected_operation(&mut self, right: &str, op: &str, left: &str, result: &str) {
+        let key = (right.to_owned(), op.to_owned(), left.to_owned());
+        self.reflected_operations.insert(key, result.to_owned());
+    }
+
+    pub fn operation_result(&self, left: &str, op: &str, right: &str) -> Option<&String> {
+        let key = (left.to_owned(), op.to_owned(), right.to_owned());
+        self.operations.get(&key)
+    }
+
+    pub fn reflected_operation_result(&self, right: &str, op: &str, left: &str) -> Option<&String> {
+        let key = (right.to_owned(), op.to_owned(), left.to_owned());
+        self.reflected_operations.get(&key)
+    }
+
     pub fn add_constant(&mut self, name: &str, address: &Pointer) {
         self.constants.insert(name.to_owned(), address.clone());
     }
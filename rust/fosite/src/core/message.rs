@@ -13,9 +13,123 @@ use std::collections::HashMap;
 
 use super::PathID;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+// positions of the nodes, and the original source text
+pub struct Sources {
+    positions: HashMap<GastID, (i16, i16)>,
+    ends: HashMap<GastID, (i16, i16)>,
+    lines: Vec<String>,
+    severities: HashMap<i16, Severity>,
+}
+
+impl Sources {
+    pub fn new() -> Sources {
+        Sources {
+            positions: HashMap::new(),
+            ends: HashMap::new(),
+            lines: Vec::new(),
+            severities: HashMap::new(),
+        }
+    }
+
+    // configured severities take precedence over the message's own
+    pub fn set_severity(&mut self, code: i16, severity: Severity) {
+        self.severities.insert(code, severity);
+    }
+
+    pub fn severity(&self, code: i16, default: Severity) -> Severity {
+        self.severities.get(&code).cloned().unwrap_or(default)
+    }
+
+    pub fn insert(&mut self, node: GastID, start: (i16, i16), end: Option<(i16, i16)>) {
+        self.positions.insert(node, start);
+
+        if let Some(end) = end {
+            self.ends.insert(node, end);
+        }
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.lines = text.lines().map(|line| line.to_owned()).collect();
+    }
+
+    pub fn get(&self, node: &GastID) -> Option<&(i16, i16)> {
+        self.positions.get(node)
+    }
+
+    pub fn get_end(&self, node: &GastID) -> Option<&(i16, i16)> {
+        self.ends.get(node)
+    }
+
+    pub fn get_line(&self, row: i16) -> Option<&String> {
+        if row < 1 {
+            return None;
+        }
+
+        self.lines.get(row as usize - 1)
+    }
+
+    // the offending line, with the indentation and width of the underline
+    // nodes without a known end don't get an underline
+    pub fn snippet(&self, node: &GastID) -> Option<(i16, &String, String, Option<usize>)> {
+        let &(row, col) = match self.get(node) {
+            Some(position) => position,
+            None => return None,
+        };
+
+        let line = match self.get_line(row) {
+            Some(line) => line,
+            None => return None,
+        };
+
+        let start = col as usize;
+
+        let width = self.get_end(node).map(|&(end_row, end_col)| {
+            let end = if end_row == row {
+                end_col as usize
+            } else {
+                line.trim_end().chars().count()
+            };
+
+            if end > start { end - start } else { 1 }
+        });
+
+        // keep tabs, so the underline lines up with the source
+        let mut indent: String = line.chars()
+            .take(start)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        while indent.chars().count() < start {
+            indent.push(' ');
+        }
+
+        Some((row, line, indent, width))
+    }
+}
+
+impl Default for Sources {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub enum Message {
     Output {
         source: PathID,
@@ -25,27 +139,88 @@ pub enum Message {
         source: GastID,
         line: i16,
         col: i16,
+        end: Option<(i16, i16)>,
         node: GastNode,
     },
+    Source {
+        text: String,
+    },
+    Suppress {
+        source: GastID,
+        codes: Vec<String>,
+        last_row: Option<i16>,
+    },
     Terminate,
 }
 
+// short description of a path node, without its location
+pub fn describe_node(node: &PathNode) -> String {
+    match *node {
+        PathNode::Condition(_, b, _) => {
+            let condition = if b == 0 { "true" } else { "false" };
+            format!("Condition is {}", condition)
+        }
+        PathNode::Loop(_) => "Iteration of the loop".to_owned(),
+        PathNode::Assignment(_, ref name) => format!("Assignment to {}", name),
+        PathNode::Deletion(_, ref name) => format!("Deletion of {}", name),
+        PathNode::Return(_) => "Return".to_owned(),
+        PathNode::Element(_, _, _) => "Element of the collection".to_owned(),
+        PathNode::Frame(_, ref target, _, _) => {
+            format!("Call to {}", target.as_ref().map(|t| t.as_str()).unwrap_or("function"))
+        }
+    }
+}
+
 pub trait MessageContent: Send {
     #[allow(ptr_arg)]
     fn hash(&self, source: &PathID) -> u64;
 
+    // message identifier, see core/output
+    fn code(&self) -> i16;
+
+    // plain text summary, None if the message isn't relevant for this node
+    fn summary(&self, nodes: &Nodes, node: &PathID) -> Option<String>;
+
+    // the paths that explain why the message was emitted
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        Vec::new()
+    }
+
     fn print_warning_preamble(&self, sources: &Sources, node: PathID) {
-        let source_node = node.last().unwrap();
-        let &(row, col) = sources.get(source_node).unwrap();
-        println!("{}",
-            Custom(220).bold().paint(format!("Warning at row {}, column {}", row, col + 1)));
+        let severity = sources.severity(self.code(), Severity::Warning);
+        self.print_preamble(sources, node, severity);
     }
 
-    fn print_error_preamble(&self,  sources: &Sources, node: PathID) {
+    fn print_error_preamble(&self, sources: &Sources, node: PathID) {
+        let severity = sources.severity(self.code(), Severity::Error);
+        self.print_preamble(sources, node, severity);
+    }
+
+    fn print_preamble(&self, sources: &Sources, node: PathID, severity: Severity) {
         let source_node = node.last().unwrap();
         let &(row, col) = sources.get(source_node).unwrap();
-        println!("{}",
-            Red.bold().paint(format!("Error at row {}, column {}", row, col + 1)));
+
+        match severity {
+            Severity::Warning => println!("{}",
+                Custom(220).bold().paint(format!("Warning at row {}, column {}", row, col + 1))),
+            Severity::Error => println!("{}",
+                Red.bold().paint(format!("Error at row {}, column {}", row, col + 1))),
+        }
+
+        self.print_snippet(sources, source_node, "  ");
+    }
+
+    fn print_snippet(&self, sources: &Sources, node: &GastID, padding: &str) {
+        if let Some((row, line, indent, width)) = sources.snippet(node) {
+            let number = format!("{}", row);
+            let gutter = " ".repeat(number.len());
+
+            println!("{}{} | {}", padding, number, line);
+
+            if let Some(width) = width {
+                println!("{}{} | {}{}", padding, gutter, indent, Red.bold().paint("^".repeat(width)));
+            }
+        }
     }
 
     fn reduce_paths(&self, sources: &Sources, paths: &BTreeSet<Path>) -> BTreeSet<Path> {
@@ -91,11 +266,13 @@ pub trait MessageContent: Send {
             for node in path {
                 let row;
                 let col;
+                let location;
                 
                 if let Some(source_node) = node.get_location().last(){
                     if let Some( &(pls1, pls2) ) = sources.get(source_node) {
                         row = pls1;
                         col = pls2;
+                        location = *source_node;
                     } else {
                         continue;
                     }                    
@@ -124,6 +301,12 @@ pub trait MessageContent: Send {
                                  Bold.paint(name),
                                  Bold.paint(format!("row {}, column {}", row, col + 1)));
                     }
+                    PathNode::Deletion(_, ref name) => {
+                        println!("{}Deletion of {} at {}",
+                                 padding,
+                                 Bold.paint(name),
+                                 Bold.paint(format!("row {}, column {}", row, col + 1)));
+                    }
                     PathNode::Return(_) => {
                         println!("{}{} {}",
                                  padding,
@@ -143,6 +326,8 @@ pub trait MessageContent: Send {
                                  Bold.paint(format!("row {}, column {}", row, col + 1)));
                     }
                 }
+
+                self.print_snippet(sources, &location, &format!("{}  ", padding));
             }
         }
     }
@@ -15,6 +15,10 @@ mod channel;
 mod executors;
 mod watch;
 mod module;
+mod sarif;
+mod diagnostic;
+mod config;
+mod baseline;
 
 mod output;
 
@@ -37,5 +41,9 @@ pub use self::channel::*;
 pub use self::executors::*;
 pub use self::watch::*;
 pub use self::module::*;
+pub use self::sarif::*;
+pub use self::diagnostic::*;
+pub use self::config::*;
+pub use self::baseline::*;
 
 pub use self::output::*;
\ No newline at end of file
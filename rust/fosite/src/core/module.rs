@@ -21,10 +21,14 @@ impl Module {
         self.parts.insert(name, part);
     }
 
+    pub fn has_part(&self, name: &str) -> bool {
+        self.parts.contains_key(name)
+    }
+
     pub fn make_object(&self, vm: &mut VirtualMachine, names: Vec<(String, String)>) -> Vec<(String, Pointer)> {
         let mut pointers = Vec::new();
 
-        if !names.is_empty() {
+        if names.is_empty() {
             for (name, part) in &self.parts {
                 let pointer = part(vm);
                 pointers.push((name.clone(), pointer));
@@ -9,6 +9,8 @@ use super::CollectionChunk;
 use super::KnowledgeBase;
 use super::PathID;
 
+use std::collections::BTreeSet;
+
 
 /// objects
 // Object is composed of several properties it may or may not have
@@ -156,6 +158,10 @@ impl Object {
         self.attributes.resolve_optional_identifier(name)
     }
 
+    pub fn attribute_names(&self) -> BTreeSet<String> {
+        self.attributes.names()
+    }
+
     pub fn get_scope_mut(&mut self) -> &mut Scope {
         &mut self.attributes
     }
@@ -189,8 +195,8 @@ impl Object {
         self.elements.get_last_n(n, node)
     }
 
-    pub fn slice_elements(&self, start: i16, end: i16) -> Vec<(Path, CollectionBranch)> {
-        self.elements.slice(start, end)
+    pub fn slice_elements(&self, start: i16, end: i16, step: Option<i16>) -> Vec<(Path, CollectionBranch)> {
+        self.elements.slice(start, end, step)
     }
 
     pub fn insert_element(&mut self, element: CollectionChunk, path: Path) {
@@ -209,6 +215,18 @@ impl Object {
         self.elements.prepend(element, path)
     }
 
+    pub fn remove_element(&mut self, index: Option<i16>, path: Path) {
+        self.elements.remove(index, path)
+    }
+
+    pub fn remove_all_elements(&mut self, path: Path) {
+        self.elements.remove_all(path)
+    }
+
+    pub fn reverse_elements(&mut self, path: Path) {
+        self.elements.reverse(path)
+    }
+
     pub fn set_elements(&mut self, content: Collection) {
         self.elements = content;
     }
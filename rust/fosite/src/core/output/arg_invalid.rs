@@ -1,5 +1,6 @@
 use super::Path;
 use super::MessageContent;
+use super::Sources;
 
 use term_painter::ToStyle;
 use term_painter::Attr::*;
@@ -10,7 +11,6 @@ use super::GastNode;
 use super::PathID;
 
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 use std::hash::{Hash, Hasher};
@@ -19,16 +19,16 @@ use std::collections::hash_map::DefaultHasher;
 use super::ARGUMENT_INVALID;
 
 pub struct ArgInvalid {
-    index: &'static str,
-    permitted: Vec<&'static str>,
+    index: String,
+    permitted: Vec<String>,
     actual: Vec<(Path, String)>,
 }
 
 impl ArgInvalid {
-    pub fn new(index: &'static str, permitted: Vec<&'static str>, actual: Vec<(Path, String)>) -> Self {
+    pub fn new(index: &str, permitted: Vec<&str>, actual: Vec<(Path, String)>) -> Self {
         ArgInvalid {
-            index: index,
-            permitted: permitted,
+            index: index.to_owned(),
+            permitted: permitted.iter().map(|t| t.to_string()).collect(),
             actual: actual,
         }
     } 
@@ -44,6 +44,20 @@ impl MessageContent for ArgInvalid {
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        ARGUMENT_INVALID
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("Invalid argument type, the {} argument should have one of the following types: {:?}",
+            &self.index,
+            &self.permitted))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.actual.iter().map(|&(ref path, _)| path.clone()).collect()
+    }
+
     fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
         self.print_error_preamble(sources, node);
         println!("  Invalid argument type");
@@ -0,0 +1,71 @@
+use super::MessageContent;
+use super::Sources;
+
+use term_painter::ToStyle;
+use term_painter::Attr::*;
+
+use std::collections::HashMap;
+use super::GastID;
+use super::GastNode;
+use super::PathID;
+
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+
+use super::Path;
+
+use super::ARITY_INVALID;
+
+type Nodes = HashMap<GastID, GastNode>;
+
+pub struct ArityInvalid {
+    signature: String,
+    problems: Vec<String>,
+    path: Path,
+}
+
+impl ArityInvalid {
+    pub fn new(signature: String, problems: Vec<String>, path: Path) -> Self {
+        ArityInvalid {
+            signature: signature,
+            problems: problems,
+            path: path,
+        }
+    }
+}
+
+impl MessageContent for ArityInvalid {
+    fn hash(&self, _: &PathID) -> u64 {
+        let mut s = DefaultHasher::new();
+        ARITY_INVALID.hash(&mut s);
+        self.signature.hash(&mut s);
+        self.problems.hash(&mut s);
+        s.finish()
+    }
+
+    fn code(&self) -> i16 {
+        ARITY_INVALID
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("Invalid arguments for {}: {}", &self.signature, self.problems.join(", ")))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        vec!(self.path.clone())
+    }
+
+    fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
+        self.print_error_preamble(sources, node);
+        println!("  Invalid arguments for a call to {}", 
+            Bold.paint(&self.signature));
+
+        for problem in &self.problems {
+            println!("    {}", problem);
+        }
+
+        println!("  In the following case:");
+        self.print_path(sources, &self.path, "    ");
+        println!("");
+    }
+}
@@ -1,5 +1,6 @@
 use super::Path;
 use super::MessageContent;
+use super::Sources;
 
 use term_painter::ToStyle;
 use term_painter::Color::*;
@@ -18,7 +19,6 @@ use std::collections::BTreeSet;
 
 use super::ATTRIBUTE_INVALID;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 pub struct AttributeInvalid {
@@ -35,6 +35,15 @@ impl AttributeInvalid {
             paths: paths,
         }
     }
+
+    // getattr calls name the object in their first argument
+    fn parent(&self, node: &GastNode) -> Option<String> {
+        match node.kind {
+            NodeType::Attribute {..} => Some(self.parent.clone()),
+            NodeType::Call {ref args, ..} if !args.is_empty() => Some(args[0].to_string()),
+            _ => None
+        }
+    }
 }
 
 impl MessageContent for AttributeInvalid {
@@ -48,18 +57,35 @@ impl MessageContent for AttributeInvalid {
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        ATTRIBUTE_INVALID
+    }
+
+    fn summary(&self, nodes: &Nodes, node: &PathID) -> Option<String> {
+        let source_node = *node.last().unwrap();
+        let node_type = nodes.get(&source_node).unwrap();
+
+        let parent = self.parent(node_type)?;
+
+        Some(format!("Object {} does not have an attribute {}", &parent, &self.attribute))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.paths.iter().cloned().collect()
+    }
+
     fn print_message(&self, sources: &Sources, nodes: &Nodes, node: PathID) {
         let source_node = *node.last().unwrap();
         let node_type = nodes.get(&source_node).unwrap();
 
-        match node_type.kind {
-            NodeType::Attribute {..} => (),
-            _ => return
-        }
+        let parent = match self.parent(node_type) {
+            Some(parent) => parent,
+            None => return
+        };
 
         self.print_error_preamble(sources, node);
         println!("  Object {} does not have an attribute {}",
-                 Bold.paint(&self.parent),
+                 Bold.paint(&parent),
                  Bold.paint(&self.attribute));
         println!("  In the following cases:");
 
@@ -1,5 +1,6 @@
 use super::Path;
 use super::MessageContent;
+use super::Sources;
 
 use term_painter::ToStyle;
 use term_painter::Color::*;
@@ -16,7 +17,6 @@ use std::collections::BTreeSet;
 
 use super::ATTRIBUTE_UNSAFE;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 
@@ -57,6 +57,18 @@ impl MessageContent for AttributeUnsafe {
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        ATTRIBUTE_UNSAFE
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("Object {} does not always have an attribute {}", &self.parent, &self.attribute))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.paths.iter().cloned().collect()
+    }
+
     fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
         self.print_warning_preamble(sources, node);
         println!("  Object {} does not always have an attribute {}",
@@ -1,5 +1,6 @@
 use super::Path;
 use super::MessageContent;
+use super::Sources;
 
 use term_painter::ToStyle;
 use term_painter::Color::*;
@@ -13,7 +14,6 @@ use super::GastNode;
 use super::PathID;
 
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 use std::hash::{Hash, Hasher};
@@ -44,6 +44,30 @@ impl MessageContent for BinOpInvalid {
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        BINOP_INVALID
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        let combinations: Vec<String> = self.combinations
+            .keys()
+            .map(|&(ref left, ref right)| format!("{} {} {}", left, self.operator, right))
+            .collect();
+
+        Some(format!("Incompatible types for operation {}: {}", &self.operator, combinations.join(", ")))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        let mut result = Vec::new();
+
+        for &(ref left_paths, ref right_paths) in self.combinations.values() {
+            result.extend(left_paths.iter().cloned());
+            result.extend(right_paths.iter().cloned());
+        }
+
+        result
+    }
+
     fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
         self.print_error_preamble(sources, node);
         println!("  Incompatible types for operation {}",
@@ -0,0 +1,77 @@
+use super::Path;
+use super::MessageContent;
+use super::Sources;
+
+use term_painter::ToStyle;
+use term_painter::Color::*;
+use term_painter::Attr::*;
+
+use std::collections::HashMap;
+use super::GastID;
+use super::GastNode;
+use super::PathID;
+
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+
+use super::OUT_OF_BOUNDS;
+
+type Nodes = HashMap<GastID, GastNode>;
+
+// popping from an empty list or set is an error as well
+pub struct EmptyPop {
+    target: String,
+    paths: Vec<Path>,
+}
+
+impl EmptyPop {
+    pub fn new(target: String, paths: Vec<Path>) -> Self {
+        EmptyPop {
+            target,
+            paths,
+        }
+    }
+}
+
+impl MessageContent for EmptyPop {
+    fn hash(&self, _: &PathID) -> u64 {
+        let mut s = DefaultHasher::new();
+        OUT_OF_BOUNDS.hash(&mut s);
+        "pop".hash(&mut s);
+        self.target.hash(&mut s);
+        self.paths.hash(&mut s);
+        s.finish()
+    }
+
+    fn code(&self) -> i16 {
+        OUT_OF_BOUNDS
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("Pop from {}, which might be empty", &self.target))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.paths.clone()
+    }
+
+    fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
+        self.print_warning_preamble(sources, node);
+        println!("  Pop from {}, which might be empty",
+                 Bold.paint(&self.target));
+        println!("  It might not have any elements in the following cases:");
+
+        for (index, path) in self.paths.iter().enumerate() {
+            println!("  Case {}",
+                    Bold.paint(format!("{}", index + 1)));
+
+            if path.is_empty() {
+                println!("    {}", Red.bold().paint("Always"));
+            } else {
+                self.print_path(sources, path, "    ");
+            }
+
+            println!();
+        }
+    }
+}
@@ -1,11 +1,11 @@
 use super::Path;
 use super::MessageContent;
+use super::Sources;
 
 use term_painter::ToStyle;
 use term_painter::Color::*;
 use term_painter::Attr::*;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 use std::collections::HashMap;
@@ -39,6 +39,18 @@ impl MessageContent for ForLoopChange {
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        FOR_LOOP_CHANGE
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some("Some code paths change the collection that's being iterated over".to_owned())
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.paths.clone()
+    }
+
     fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
         self.print_warning_preamble(sources, node);
         println!("  Some code paths change the collection that's being iterated over");
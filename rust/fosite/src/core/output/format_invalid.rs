@@ -0,0 +1,80 @@
+use super::MessageContent;
+use super::Sources;
+
+use term_painter::ToStyle;
+use term_painter::Color::*;
+use term_painter::Attr::*;
+
+use std::collections::HashMap;
+use super::GastID;
+use super::GastNode;
+use super::PathID;
+
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+
+use super::Path;
+
+use super::FORMAT_INVALID;
+
+type Nodes = HashMap<GastID, GastNode>;
+
+pub struct FormatInvalid {
+    format: String,
+    problem: String,
+    paths: Vec<Path>,
+}
+
+impl FormatInvalid {
+    pub fn new(format: String, problem: String, paths: Vec<Path>) -> Self {
+        FormatInvalid {
+            format: format,
+            problem: problem,
+            paths: paths,
+        }
+    }
+}
+
+impl MessageContent for FormatInvalid {
+    fn hash(&self, _: &PathID) -> u64 {
+        let mut s = DefaultHasher::new();
+        FORMAT_INVALID.hash(&mut s);
+        self.format.hash(&mut s);
+        self.problem.hash(&mut s);
+        self.paths.hash(&mut s);
+        s.finish()
+    }
+
+    fn code(&self) -> i16 {
+        FORMAT_INVALID
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("Format string {} does not match its arguments: {}", &self.format, &self.problem))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.paths.clone()
+    }
+
+    fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
+        self.print_error_preamble(sources, node);
+        println!("  Format string {} does not match its arguments",
+                 Bold.paint(&self.format));
+        println!("    {}", &self.problem);
+        println!("  In the following cases:");
+
+        for (index, path) in self.paths.iter().enumerate() {
+            println!("  Case {}",
+                    Bold.paint(format!("{}", index + 1)));
+
+            if path.is_empty() {
+                println!("    {}", Red.bold().paint("Always"));
+            } else {
+                self.print_path(sources, path, "    ");
+            }
+
+            println!("");
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use super::MessageContent;
+use super::Sources;
+
+use term_painter::ToStyle;
+use term_painter::Attr::*;
+
+use std::collections::HashMap;
+use super::GastID;
+use super::GastNode;
+use super::PathID;
+
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+
+use std::collections::BTreeMap;
+
+use super::Path;
+
+use super::HASH_INVALID;
+
+type Nodes = HashMap<GastID, GastNode>;
+
+pub struct HashInvalid {
+    target: String,
+    types: BTreeMap<String, Vec<Path>>,
+}
+
+impl HashInvalid {
+    pub fn new(target: String, types: BTreeMap<String, Vec<Path>>) -> Self {
+        HashInvalid {
+            target,
+            types,
+        }
+    }
+}
+
+impl MessageContent for HashInvalid {
+    fn hash(&self, _: &PathID) -> u64 {
+        let mut s = DefaultHasher::new();
+
+        HASH_INVALID.hash(&mut s);
+        self.target.hash(&mut s);
+        self.types.hash(&mut s);
+        s.finish()
+    }
+
+    fn code(&self) -> i16 {
+        HASH_INVALID
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("Unhashable object used in {}", &self.target))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.types.values().flat_map(|paths| paths.iter().cloned()).collect()
+    }
+
+    fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
+        self.print_error_preamble(sources, node);
+        println!("  {} can only contain hashable objects", 
+            Bold.paint(&self.target));
+        println!("  An unhashable object is inserted in the following cases:");
+
+        for (t_index, (t, paths)) in self.types.iter().enumerate() {
+            println!("  Type {}: {}",
+                t_index + 1,
+                Bold.paint(t.clone()));
+
+            for path in paths.iter() {
+                self.print_path(sources, path, "    ");
+                println!();
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use super::MessageContent;
+use super::Sources;
 
 use term_painter::ToStyle;
 use term_painter::Attr::*;
@@ -13,7 +14,6 @@ use std::collections::hash_map::DefaultHasher;
 
 use super::HETERO_COLLECTION;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 pub struct HeteroCollection {
@@ -43,6 +43,17 @@ impl MessageContent for HeteroCollection {
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        HETERO_COLLECTION
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("Adding an element of a new type to a collection, {} had type {} and became {}",
+            &self.target,
+            &self.old_type,
+            &self.new_type))
+    }
+
     fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
         self.print_warning_preamble(sources, node);
         println!("  Adding an element of a new type to a collection");
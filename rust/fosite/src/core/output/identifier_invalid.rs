@@ -1,12 +1,12 @@
 
 use super::Path;
 use super::MessageContent;
+use super::Sources;
 
 use term_painter::ToStyle;
 use term_painter::Color::*;
 use term_painter::Attr::*;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 use std::collections::HashMap;
@@ -46,6 +46,26 @@ impl MessageContent for IdentifierInvalid {
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        IDENTIFIER_INVALID
+    }
+
+    fn summary(&self, nodes: &Nodes, node: &PathID) -> Option<String> {
+        let source_node = *node.last().unwrap();
+        let node_type = nodes.get(&source_node).unwrap();
+
+        match node_type.kind {
+            NodeType::Identifier {..} => (),
+            _ => return None
+        }
+
+        Some(format!("{} does not exist", &self.name))
+    }
+
+    fn paths(&self, sources: &Sources) -> Vec<Path> {
+        self.reduce_paths(sources, &self.paths).into_iter().collect()
+    }
+
     fn print_message(&self, sources: &Sources, nodes: &Nodes, node: PathID) {
         let source_node = *node.last().unwrap();
         let node_type = nodes.get(&source_node).unwrap();
@@ -1,5 +1,6 @@
 use super::Path;
 use super::MessageContent;
+use super::Sources;
 
 use std::hash::{Hash, Hasher};
 use std::collections::hash_map::DefaultHasher;
@@ -18,7 +19,6 @@ use std::collections::BTreeSet;
 
 use super::IDENTIFIER_UNSAFE;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 pub struct IdentifierUnsafe {
@@ -54,6 +54,18 @@ impl MessageContent for IdentifierUnsafe {
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        IDENTIFIER_UNSAFE
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("New variable {} doesn't always exist", &self.name))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.paths.iter().cloned().collect()
+    }
+
     fn print_message(&self, source: &Sources, _: &Nodes, node: PathID) {
         self.print_warning_preamble(source, node);
         println!("  New variable {} doesn't always exist",
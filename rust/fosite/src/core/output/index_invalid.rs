@@ -1,4 +1,5 @@
 use super::MessageContent;
+use super::Sources;
 
 use term_painter::ToStyle;
 use term_painter::Attr::*;
@@ -18,7 +19,6 @@ use super::Path;
 
 use super::INDEX_INVALID;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 pub struct IndexInvalid {
@@ -45,6 +45,18 @@ impl MessageContent for IndexInvalid {
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        INDEX_INVALID
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("{} does not support indexing", &self.target))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.types.values().flat_map(|paths| paths.iter().cloned()).collect()
+    }
+
     fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
         self.print_error_preamble(sources, node);
         println!("  {} does not support indexing", 
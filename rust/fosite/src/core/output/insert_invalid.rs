@@ -1,4 +1,5 @@
 use super::MessageContent;
+use super::Sources;
 
 use term_painter::ToStyle;
 use term_painter::Attr::*;
@@ -16,7 +17,6 @@ use std::collections::BTreeMap;
 
 use super::Path;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 use super::INSERT_INVALID;
@@ -45,6 +45,18 @@ impl MessageContent for InsertInvalid {
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        INSERT_INVALID
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("{} does not support item assignment", &self.target))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.types.values().flat_map(|paths| paths.iter().cloned()).collect()
+    }
+
     fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
         self.print_error_preamble(sources, node);
         println!("  {} does not support item assignment", 
@@ -0,0 +1,79 @@
+use super::Path;
+use super::MessageContent;
+use super::Sources;
+
+use term_painter::ToStyle;
+use term_painter::Color::*;
+use term_painter::Attr::*;
+
+use std::collections::HashMap;
+use super::GastID;
+use super::GastNode;
+use super::PathID;
+
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+
+use super::KEY_MISSING;
+
+type Nodes = HashMap<GastID, GastNode>;
+
+pub struct KeyMissing {
+    target: String,
+    key: String,
+    paths: Vec<Path>,
+}
+
+impl KeyMissing {
+    pub fn new(target: String, key: String, paths: Vec<Path>) -> Self {
+        KeyMissing {
+            target,
+            key,
+            paths,
+        }
+    }
+}
+
+impl MessageContent for KeyMissing {
+    fn hash(&self, _: &PathID) -> u64 {
+        let mut s = DefaultHasher::new();
+        KEY_MISSING.hash(&mut s);
+        self.target.hash(&mut s);
+        self.key.hash(&mut s);
+        self.paths.hash(&mut s);
+        s.finish()
+    }
+
+    fn code(&self) -> i16 {
+        KEY_MISSING
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("Key {} might not be in {}", &self.key, &self.target))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.paths.clone()
+    }
+
+    fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
+        self.print_warning_preamble(sources, node);
+        println!("  Key {} might not be in {}",
+                 Bold.paint(&self.key),
+                 Bold.paint(&self.target));
+        println!("  It was never inserted in the following cases:");
+
+        for (index, path) in self.paths.iter().enumerate() {
+            println!("  Case {}",
+                    Bold.paint(format!("{}", index + 1)));
+
+            if path.is_empty() {
+                println!("    {}", Red.bold().paint("Always"));
+            } else {
+                self.print_path(sources, path, "    ");
+            }
+
+            println!();
+        }
+    }
+}
@@ -7,6 +7,8 @@ mod while_loop_change;
 mod hetero_collection;
 mod for_loop_change;
 mod no_return;
+mod key_missing;
+mod empty_pop;
 
 pub use self::identifier_unsafe::*;
 pub use self::attribute_unsafe::*;
@@ -16,6 +18,8 @@ pub use self::while_loop_change::*;
 pub use self::hetero_collection::*;
 pub use self::for_loop_change::*;
 pub use self::no_return::*;
+pub use self::key_missing::*;
+pub use self::empty_pop::*;
 
 pub use super::*;
 
@@ -26,6 +30,11 @@ mod binop_invalid;
 mod insert_invalid;
 mod index_invalid;
 mod arg_invalid;
+mod hash_invalid;
+mod arity_invalid;
+mod step_invalid;
+mod unbound_local;
+mod format_invalid;
 
 pub use self::identifier_invalid::*;
 pub use self::attribute_invalid::*;
@@ -33,6 +42,11 @@ pub use self::binop_invalid::*;
 pub use self::insert_invalid::*;
 pub use self::index_invalid::*;
 pub use self::arg_invalid::*;
+pub use self::hash_invalid::*;
+pub use self::arity_invalid::*;
+pub use self::step_invalid::*;
+pub use self::unbound_local::*;
+pub use self::format_invalid::*;
 
 // message identifiers, used in the hashing
 pub const IDENTIFIER_UNSAFE: i16 = 1;
@@ -43,10 +57,51 @@ pub const WHILE_LOOP_CHANGE: i16 = 5;
 pub const HETERO_COLLECTION: i16 = 6;
 pub const FOR_LOOP_CHANGE: i16 = 7;
 pub const NO_RETURN: i16 = 8;
+pub const KEY_MISSING: i16 = 9;
 
 pub const IDENTIFIER_INVALID: i16 = -1;
 pub const ATTRIBUTE_INVALID: i16 = -2;
 pub const BINOP_INVALID: i16 = -3;
 pub const INSERT_INVALID: i16 = -4;
 pub const INDEX_INVALID: i16 = -5;
-pub const ARGUMENT_INVALID: i16 = -6;
\ No newline at end of file
+pub const ARGUMENT_INVALID: i16 = -6;
+pub const HASH_INVALID: i16 = -7;
+pub const ARITY_INVALID: i16 = -8;
+pub const STEP_INVALID: i16 = -9;
+pub const UNBOUND_LOCAL: i16 = -10;
+pub const FORMAT_INVALID: i16 = -11;
+// rule metadata, used by the machine readable output formats
+pub static RULES: &'static [(i16, &'static str, &'static str)] = &[
+    (IDENTIFIER_UNSAFE, "IDENTIFIER_UNSAFE", "A variable doesn't exist in every code path"),
+    (ATTRIBUTE_UNSAFE, "ATTRIBUTE_UNSAFE", "An attribute doesn't exist in every code path"),
+    (OUT_OF_BOUNDS, "OUT_OF_BOUNDS", "An index might be out of bounds"),
+    (TYPE_UNSAFE, "TYPE_UNSAFE", "A variable has a different type depending on the code path"),
+    (WHILE_LOOP_CHANGE, "WHILE_LOOP_CHANGE", "Not all code paths update the loop condition"),
+    (HETERO_COLLECTION, "HETERO_COLLECTION", "An element of a new type is added to a collection"),
+    (FOR_LOOP_CHANGE, "FOR_LOOP_CHANGE", "The collection that's being iterated over is changed"),
+    (NO_RETURN, "NO_RETURN", "Not all code paths return a value"),
+    (KEY_MISSING, "KEY_MISSING", "A key might not be in a dictionary"),
+    (IDENTIFIER_INVALID, "IDENTIFIER_INVALID", "A variable does not exist"),
+    (ATTRIBUTE_INVALID, "ATTRIBUTE_INVALID", "An attribute does not exist"),
+    (BINOP_INVALID, "BINOP_INVALID", "Incompatible types for a binary operation"),
+    (INSERT_INVALID, "INSERT_INVALID", "An object does not support item assignment"),
+    (INDEX_INVALID, "INDEX_INVALID", "An object does not support indexing"),
+    (ARGUMENT_INVALID, "ARGUMENT_INVALID", "An argument has an invalid type"),
+    (HASH_INVALID, "HASH_INVALID", "An unhashable object is used as a key or set element"),
+    (ARITY_INVALID, "ARITY_INVALID", "A function is called with the wrong arguments"),
+    (STEP_INVALID, "STEP_INVALID", "A slice has a step of zero"),
+    (UNBOUND_LOCAL, "UNBOUND_LOCAL", "A local variable is read before it's assigned"),
+    (FORMAT_INVALID, "FORMAT_INVALID", "A format string does not match its arguments"),
+];
+
+pub fn rule_index(code: i16) -> Option<usize> {
+    RULES.iter().position(|&(c, _, _)| c == code)
+}
+
+pub fn rule_severity(code: i16) -> Severity {
+    if code < 0 {
+        Severity::Error
+    } else {
+        Severity::Warning
+    }
+}
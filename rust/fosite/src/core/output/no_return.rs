@@ -1,11 +1,11 @@
 use super::Path;
 use super::MessageContent;
+use super::Sources;
 
 use term_painter::ToStyle;
 use term_painter::Color::*;
 use term_painter::Attr::*;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 use std::collections::HashMap;
@@ -41,6 +41,18 @@ impl MessageContent for NoReturn {
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        NO_RETURN
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some("Not all code paths have returned a value".to_owned())
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.paths.iter().cloned().collect()
+    }
+
     fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
         self.print_warning_preamble(sources, node);
         println!("  Not all code paths have returned a value");
@@ -1,5 +1,6 @@
 use super::Path;
 use super::MessageContent;
+use super::Sources;
 
 use term_painter::ToStyle;
 use term_painter::Color::*;
@@ -16,7 +17,6 @@ use std::collections::hash_map::DefaultHasher;
 
 use super::OUT_OF_BOUNDS;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 pub struct OutOfBounds {
@@ -35,7 +35,7 @@ impl OutOfBounds {
 
 
 impl MessageContent for OutOfBounds {
-    fn hash(&self, _: &PathID) -> u64 {
+    fn hash(&self, source: &PathID) -> u64 {
         let mut s = DefaultHasher::new();
 
         let mut fingerprint = Path::empty();
@@ -49,9 +49,22 @@ impl MessageContent for OutOfBounds {
         OUT_OF_BOUNDS.hash(&mut s);
         self.target.hash(&mut s);
         fingerprint.hash(&mut s);
+        source.hash(&mut s);
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        OUT_OF_BOUNDS
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("Index might be out of bounds, {} does not always have enough elements", &self.target))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.cases.iter().map(|&(ref path, _)| path.clone()).collect()
+    }
+
     fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
         self.print_warning_preamble(sources, node);
         println!("  Index might be out of bounds");
@@ -60,10 +73,10 @@ impl MessageContent for OutOfBounds {
         println!("  In the following cases:");
 
 
-        for (index, &(ref path, max)) in self.cases.iter().enumerate() {
+        for (index, &(ref path, size)) in self.cases.iter().enumerate() {
             println!("  Case {}",
                     Bold.paint(format!("{}", index + 1)));
-            println!("    {} has at most {} elements in the following case", self.target, max);
+            println!("    {} might have only {} elements in the following case", self.target, size);
             if path.is_empty() {
                 self.print_path(sources, path, "    ");
             } else {
@@ -0,0 +1,75 @@
+use super::MessageContent;
+use super::Sources;
+
+use term_painter::ToStyle;
+use term_painter::Color::*;
+use term_painter::Attr::*;
+
+use std::collections::HashMap;
+use super::GastID;
+use super::GastNode;
+use super::PathID;
+
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+
+use super::Path;
+
+use super::STEP_INVALID;
+
+type Nodes = HashMap<GastID, GastNode>;
+
+pub struct StepInvalid {
+    target: String,
+    path: Path,
+}
+
+impl StepInvalid {
+    pub fn new(target: String, path: Path) -> Self {
+        StepInvalid {
+            target: target,
+            path: path,
+        }
+    }
+}
+
+impl MessageContent for StepInvalid {
+    fn hash(&self, _: &PathID) -> u64 {
+        let mut s = DefaultHasher::new();
+        STEP_INVALID.hash(&mut s);
+        self.target.hash(&mut s);
+        s.finish()
+    }
+
+    fn code(&self) -> i16 {
+        STEP_INVALID
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("Slice of {} has a step of zero", &self.target))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        vec!(self.path.clone())
+    }
+
+    fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
+        self.print_error_preamble(sources, node);
+        println!("  The slice of {} has a step of zero, this raises a ValueError", 
+            Bold.paint(&self.target));
+        println!("  In the following case:");
+
+        // the frame of the module itself isn't shown
+        let located = self.path._iter().any(|node| {
+            node.get_location().last().map_or(false, |location| sources.get(location).is_some())
+        });
+
+        if !located {
+            println!("    {}", Red.bold().paint("Always"));
+        } else {
+            self.print_path(sources, &self.path, "    ");
+        }
+
+        println!("");
+    }
+}
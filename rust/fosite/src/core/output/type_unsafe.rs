@@ -1,5 +1,6 @@
 use super::Path;
 use super::MessageContent;
+use super::Sources;
 
 use term_painter::ToStyle;
 use term_painter::Attr::*;
@@ -18,7 +19,6 @@ use super::PathID;
 
 use super::TYPE_UNSAFE;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 pub struct TypeUnsafe {
@@ -55,6 +55,18 @@ impl MessageContent for TypeUnsafe {
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        TYPE_UNSAFE
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("Not all code paths give {} the same type", &self.name))
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.types.values().flat_map(|paths| paths.iter().cloned()).collect()
+    }
+
     fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
         self.print_warning_preamble(sources, node);
         println!("  Not all code paths give {} the same type",
@@ -0,0 +1,80 @@
+use super::Path;
+use super::MessageContent;
+use super::Sources;
+
+use term_painter::ToStyle;
+use term_painter::Color::*;
+use term_painter::Attr::*;
+
+use std::collections::HashMap;
+use super::GastID;
+use super::GastNode;
+use super::PathID;
+
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeSet;
+
+use super::UNBOUND_LOCAL;
+
+type Nodes = HashMap<GastID, GastNode>;
+
+// a name that gets assigned anywhere in a function is local to all of it
+pub struct UnboundLocal {
+    name: String,
+    paths: BTreeSet<Path>,
+}
+
+impl UnboundLocal {
+    pub fn new(name: String, paths: BTreeSet<Path>) -> Self {
+        UnboundLocal {
+            name: name,
+            paths: paths,
+        }
+    }
+}
+
+impl MessageContent for UnboundLocal {
+    fn hash(&self, source: &PathID) -> u64 {
+        let mut s = DefaultHasher::new();
+        UNBOUND_LOCAL.hash(&mut s);
+        self.name.hash(&mut s);
+        self.paths.hash(&mut s);
+        source.hash(&mut s);
+        s.finish()
+    }
+
+    fn code(&self) -> i16 {
+        UNBOUND_LOCAL
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some(format!("Local variable {} is read before it's assigned", &self.name))
+    }
+
+    fn paths(&self, sources: &Sources) -> Vec<Path> {
+        self.reduce_paths(sources, &self.paths).into_iter().collect()
+    }
+
+    fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
+        self.print_error_preamble(sources, node);
+        println!("  {} is assigned in this function, which makes it a local variable",
+                 Bold.paint(&self.name));
+        println!("  It isn't assigned yet in the following cases:");
+
+        let relevant_paths = self.reduce_paths(sources, &self.paths);
+
+        for (index, path) in relevant_paths.iter().enumerate() {
+            println!("  Case {}",
+                    Bold.paint(format!("{}", index + 1)));
+
+            if path.is_empty() {
+                println!("    {}", Red.bold().paint("Always"));
+            } else {
+                self.print_path(sources, path, "    ");
+            }
+
+            println!("");
+        }
+    }
+}
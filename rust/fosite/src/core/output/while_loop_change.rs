@@ -1,11 +1,11 @@
 use super::Path;
 use super::MessageContent;
+use super::Sources;
 
 use term_painter::ToStyle;
 use term_painter::Color::*;
 use term_painter::Attr::*;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 use std::collections::HashMap;
@@ -40,6 +40,18 @@ impl MessageContent for WhileLoopChange {
         s.finish()
     }
 
+    fn code(&self) -> i16 {
+        WHILE_LOOP_CHANGE
+    }
+
+    fn summary(&self, _: &Nodes, _: &PathID) -> Option<String> {
+        Some("Not all code paths update the loop condition".to_owned())
+    }
+
+    fn paths(&self, _: &Sources) -> Vec<Path> {
+        self.paths.clone()
+    }
+
     fn print_message(&self, sources: &Sources, _: &Nodes, node: PathID) {
         self.print_warning_preamble(sources, node);
         println!("  Not all code paths update the loop condition");
@@ -12,6 +12,7 @@ pub type PathID = Vec<GastID>;
 pub enum PathNode {
     Condition(PathID, i16, i16),
     Assignment(PathID, String),
+    Deletion(PathID, String),
     Loop(PathID),
     Return(PathID),
     Frame(PathID, Option<String>, i16, i16),
@@ -100,7 +101,8 @@ impl Hash for PathNode {
 impl PathNode {
     pub fn is_assign(&self) -> bool {
         match *self {
-            PathNode::Assignment(_, _) => true,
+            PathNode::Assignment(_, _) |
+            PathNode::Deletion(_, _) => true,
             _ => false,
         }
     }
@@ -131,6 +133,7 @@ impl PathNode {
         match *self {
             PathNode::Condition(ref location, _, _) |
             PathNode::Assignment(ref location, _) |
+            PathNode::Deletion(ref location, _) |
             PathNode::Loop(ref location) |
             PathNode::Return(ref location) |
             PathNode::Frame(ref location, _, _, _) |
@@ -158,7 +161,8 @@ impl PathNode {
             }
             (&PathNode::Return(ref l1), &PathNode::Return(ref l2)) |
             (&PathNode::Loop(ref l1), &PathNode::Loop(ref l2)) |
-            (&PathNode::Assignment(ref l1, ..), &PathNode::Assignment(ref l2, ..)) => {
+            (&PathNode::Assignment(ref l1, ..), &PathNode::Assignment(ref l2, ..)) |
+            (&PathNode::Deletion(ref l1, ..), &PathNode::Deletion(ref l2, ..)) => {
                 l1 == l2
             }
             _ => false,
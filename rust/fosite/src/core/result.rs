@@ -20,6 +20,9 @@ pub struct ExecutionResult {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum AnalysisItem {
     Identifier(String),
+    // identifiers that got declared `global` or `nonlocal`
+    Global(String),
+    Nonlocal(String),
     Object(Pointer),
     Attribute(Box<AnalysisItem>, String),
 }
@@ -27,7 +30,9 @@ pub enum AnalysisItem {
 impl AnalysisItem {
     pub fn to_string(&self) -> String {
         match self {
-            &AnalysisItem::Identifier ( ref name ) => name.clone(),
+            &AnalysisItem::Identifier ( ref name ) |
+            &AnalysisItem::Global ( ref name ) |
+            &AnalysisItem::Nonlocal ( ref name ) => name.clone(),
             &AnalysisItem::Object ( ref address ) => format!("{}", address),
             &AnalysisItem::Attribute ( ref parent, ref name ) => {
                 format!("{}.{}", parent.to_string(), name)
@@ -49,6 +54,16 @@ impl AnalysisItem {
         }
     }
 
+    // changes that are still visible after a call returns
+    pub fn escapes_call(&self) -> bool {
+        match self {
+            &AnalysisItem::Object ( _ ) |
+            &AnalysisItem::Global ( _ ) |
+            &AnalysisItem::Nonlocal ( _ ) => true,
+            _ => false,
+        }
+    }
+
     pub fn is_attribute(&self) -> bool {
         match self {
             &AnalysisItem::Attribute ( _, _ ) => true,
@@ -0,0 +1,175 @@
+use rustc_serialize::json::Json;
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use super::GastID;
+use super::GastNode;
+use super::Path;
+use super::PathID;
+use super::describe_node;
+use super::MessageContent;
+use super::Sources;
+use super::RULES;
+use super::rule_index;
+use super::rule_severity;
+
+type Nodes = HashMap<GastID, GastNode>;
+
+const SCHEMA: &'static str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+// collects the emitted messages as SARIF 2.1.0 results
+pub struct Sarif {
+    uri: String,
+    results: Vec<Json>,
+}
+
+impl Sarif {
+    pub fn new(uri: String) -> Sarif {
+        Sarif {
+            uri: uri,
+            results: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, content: &MessageContent, hash: u64, sources: &Sources, nodes: &Nodes, node: &PathID) {
+        let text = match content.summary(nodes, node) {
+            Some(text) => text,
+            None => return,
+        };
+
+        let location = match node.last().and_then(|source| sources.get(source)) {
+            Some(&(row, col)) => self.location(row, col, sources.get_end(node.last().unwrap())),
+            None => return,
+        };
+
+        let code = content.code();
+        let mut result = BTreeMap::new();
+
+        if let Some(index) = rule_index(code) {
+            let (_, name, _) = RULES[index];
+            result.insert("ruleId".to_owned(), Json::String(name.to_owned()));
+            result.insert("ruleIndex".to_owned(), Json::U64(index as u64));
+        }
+
+        let severity = sources.severity(code, rule_severity(code));
+        result.insert("level".to_owned(), Json::String(severity.name().to_owned()));
+        result.insert("message".to_owned(), message(text));
+        result.insert("locations".to_owned(), Json::Array(vec![location]));
+
+        let mut fingerprints = BTreeMap::new();
+        fingerprints.insert("fosite/v1".to_owned(), Json::String(format!("{:016x}", hash)));
+        result.insert("partialFingerprints".to_owned(), Json::Object(fingerprints));
+
+        let thread_flows: Vec<Json> = content.paths(sources)
+            .iter()
+            .filter(|path| !path.is_empty())
+            .filter_map(|path| self.thread_flow(sources, path))
+            .collect();
+
+        if !thread_flows.is_empty() {
+            let mut code_flow = BTreeMap::new();
+            code_flow.insert("threadFlows".to_owned(), Json::Array(thread_flows));
+            result.insert("codeFlows".to_owned(), Json::Array(vec![Json::Object(code_flow)]));
+        }
+
+        self.results.push(Json::Object(result));
+    }
+
+    pub fn to_json(&self) -> Json {
+        let rules = RULES.iter()
+            .map(|&(code, name, description)| {
+                let mut level = BTreeMap::new();
+                level.insert("level".to_owned(), Json::String(rule_severity(code).name().to_owned()));
+
+                let mut rule = BTreeMap::new();
+                rule.insert("id".to_owned(), Json::String(name.to_owned()));
+                rule.insert("shortDescription".to_owned(), message(description.to_owned()));
+                rule.insert("defaultConfiguration".to_owned(), Json::Object(level));
+                Json::Object(rule)
+            })
+            .collect();
+
+        let mut driver = BTreeMap::new();
+        driver.insert("name".to_owned(), Json::String("fosite".to_owned()));
+        driver.insert("version".to_owned(), Json::String(env!("CARGO_PKG_VERSION").to_owned()));
+        driver.insert("rules".to_owned(), Json::Array(rules));
+
+        let mut tool = BTreeMap::new();
+        tool.insert("driver".to_owned(), Json::Object(driver));
+
+        let mut run = BTreeMap::new();
+        run.insert("tool".to_owned(), Json::Object(tool));
+        run.insert("results".to_owned(), Json::Array(self.results.clone()));
+
+        let mut log = BTreeMap::new();
+        log.insert("$schema".to_owned(), Json::String(SCHEMA.to_owned()));
+        log.insert("version".to_owned(), Json::String("2.1.0".to_owned()));
+        log.insert("runs".to_owned(), Json::Array(vec![Json::Object(run)]));
+
+        Json::Object(log)
+    }
+
+    fn location(&self, row: i16, col: i16, end: Option<&(i16, i16)>) -> Json {
+        let mut artifact = BTreeMap::new();
+        artifact.insert("uri".to_owned(), Json::String(self.uri.clone()));
+
+        let mut region = BTreeMap::new();
+        region.insert("startLine".to_owned(), Json::I64(row as i64));
+        region.insert("startColumn".to_owned(), Json::I64(col as i64 + 1));
+
+        if let Some(&(end_row, end_col)) = end {
+            region.insert("endLine".to_owned(), Json::I64(end_row as i64));
+            region.insert("endColumn".to_owned(), Json::I64(end_col as i64 + 1));
+        }
+
+        let mut physical = BTreeMap::new();
+        physical.insert("artifactLocation".to_owned(), Json::Object(artifact));
+        physical.insert("region".to_owned(), Json::Object(region));
+
+        let mut location = BTreeMap::new();
+        location.insert("physicalLocation".to_owned(), Json::Object(physical));
+        Json::Object(location)
+    }
+
+    fn thread_flow(&self, sources: &Sources, path: &Path) -> Option<Json> {
+        let mut locations = Vec::new();
+
+        for node in path {
+            let source = match node.get_location().last() {
+                Some(source) => source,
+                None => continue,
+            };
+
+            let &(row, col) = match sources.get(source) {
+                Some(position) => position,
+                None => continue,
+            };
+
+            let mut location = match self.location(row, col, sources.get_end(source)) {
+                Json::Object(location) => location,
+                _ => unreachable!(),
+            };
+
+            location.insert("message".to_owned(), message(describe_node(node)));
+
+            let mut flow_location = BTreeMap::new();
+            flow_location.insert("location".to_owned(), Json::Object(location));
+            locations.push(Json::Object(flow_location));
+        }
+
+        if locations.is_empty() {
+            return None;
+        }
+
+        let mut thread_flow = BTreeMap::new();
+        thread_flow.insert("locations".to_owned(), Json::Array(locations));
+        Some(Json::Object(thread_flow))
+    }
+}
+
+fn message(text: String) -> Json {
+    let mut message = BTreeMap::new();
+    message.insert("text".to_owned(), Json::String(text));
+    Json::Object(message)
+}
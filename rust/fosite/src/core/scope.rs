@@ -256,6 +256,11 @@ pub struct Scope {
     frames: Vec<Frame>,
     default: OptionalMapping,
     constants: BTreeSet<String>,
+    // names that live in an outer scope, see `global` and `nonlocal`
+    globals: BTreeSet<String>,
+    nonlocals: BTreeSet<String>,
+    // names that are assigned somewhere in the function
+    locals: BTreeSet<String>,
 }
 
 impl Scope {
@@ -267,13 +272,49 @@ impl Scope {
             frames: vec![],
             default: default,
             constants: BTreeSet::new(),
+            globals: BTreeSet::new(),
+            nonlocals: BTreeSet::new(),
+            locals: BTreeSet::new(),
         }
     }
 
+    pub fn declare_global(&mut self, name: String) {
+        self.globals.insert(name);
+    }
+
+    pub fn declare_nonlocal(&mut self, name: String) {
+        self.nonlocals.insert(name);
+    }
+
+    pub fn declare_local(&mut self, name: String) {
+        self.locals.insert(name);
+    }
+
+    pub fn is_global(&self, name: &str) -> bool {
+        self.globals.contains(name)
+    }
+
+    pub fn is_nonlocal(&self, name: &str) -> bool {
+        self.nonlocals.contains(name)
+    }
+
+    pub fn is_local(&self, name: &str) -> bool {
+        self.locals.contains(name)
+    }
+
     pub fn num_frames(&self) -> usize {
         self.frames.len()
     }
 
+    // the names that are set on the current branches
+    pub fn names(&self) -> BTreeSet<String> {
+        self.frames
+            .iter()
+            .flat_map(|frame| frame.branches[frame.current].content.content.keys())
+            .cloned()
+            .collect()
+    }
+
     pub fn resolve_optional_identifier(&self, name: &str) -> &OptionalMapping {
         if self.frames.len() > 0 {
             let mut index = self.frames.len() - 1;
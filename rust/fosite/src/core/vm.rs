@@ -29,8 +29,17 @@ pub struct VirtualMachine {
     closures: HashMap<Pointer, Scope>,
     results: Vec<Vec<(Path, Mapping)>>,
 
+    // loops
+    breaks: Vec<Vec<Path>>,
+
     // modules 
     modules: HashMap<String, Module>,
+
+    // types of the loaded identifiers, per node
+    inferred: HashMap<GastID, BTreeSet<String>>,
+
+    // values of the string literals, for getattr and friends
+    strings: HashMap<Pointer, String>,
 }
 
 impl Default for VirtualMachine {
@@ -59,7 +68,10 @@ impl VirtualMachine {
             callables: HashMap::new(),
             closures: HashMap::new(),
             results: Vec::new(),
+            breaks: Vec::new(),
             modules: HashMap::new(),
+            inferred: HashMap::new(),
+            strings: HashMap::new(),
         }
     }
 
@@ -125,6 +137,21 @@ impl VirtualMachine {
         self.results.push(Vec::new());
     }
 
+    // the paths on which the innermost loop breaks
+    pub fn new_breaks(&mut self) {
+        self.breaks.push(Vec::new());
+    }
+
+    pub fn add_break(&mut self, path: Path) {
+        if let Some(b) = self.breaks.last_mut() {
+            b.push(path);
+        }
+    }
+
+    pub fn get_breaks(&mut self) -> Vec<Path> {
+        self.breaks.pop().unwrap()
+    }
+
     pub fn call(&mut self, 
                 executors: &Executors, 
                 address: &Pointer, 
@@ -153,7 +180,7 @@ impl VirtualMachine {
         // local scope
         self.scopes.push(Scope::new());
 
-        let analysis;
+        let mut analysis;
         // take ownership of the callable
         if let Some(callable) = self.callables.remove(address) {
             {
@@ -167,7 +194,15 @@ impl VirtualMachine {
         }
 
         // remove the function scope
-        let _ = self.scopes.pop();
+        let local = self.scopes.pop().unwrap();
+
+        // nonlocal changes only reach the caller if this function declared them
+        if let Some(ref mut analysis) = analysis {
+            analysis.changes.retain(|change| match *change {
+                AnalysisItem::Nonlocal(ref name) => local.is_nonlocal(name),
+                _ => true,
+            });
+        }
 
         // remove the enclosing scope
         let closure = self.scopes.pop().unwrap();
@@ -233,6 +268,94 @@ impl VirtualMachine {
         self.scopes.last_mut().unwrap()
     }
 
+    // the change an assignment to this name makes
+    pub fn identifier_item(&self, name: &str) -> AnalysisItem {
+        // the module scope comes after the builtins
+        if self.scopes.len() > 2 {
+            let scope = self.scopes.last().unwrap();
+            if scope.is_global(name) {
+                return AnalysisItem::Global(name.to_owned());
+            } else if scope.is_nonlocal(name) {
+                return AnalysisItem::Nonlocal(name.to_owned());
+            }
+        }
+
+        AnalysisItem::Identifier(name.to_owned())
+    }
+
+    // the scope that holds an identifier, as (is shadowed, index)
+    fn scope_index(&self, item: &AnalysisItem) -> Option<(bool, usize)> {
+        match *item {
+            AnalysisItem::Identifier(_) => Some((false, self.scopes.len() - 1)),
+            AnalysisItem::Global(_) => Some((false, 1)),
+            AnalysisItem::Nonlocal(ref name) => {
+                let last = self.scopes.len() - 1;
+
+                if !self.scopes[last].is_nonlocal(name) {
+                    // back in the enclosing function
+                    Some((false, last))
+                } else if !self.shadow_scopes.is_empty() {
+                    // the function that called this one
+                    Some((true, self.shadow_scopes.len() - 1))
+                } else {
+                    // the closure is the best there is
+                    Some((false, last - 1))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn scope_mut(&mut self, index: (bool, usize)) -> &mut Scope {
+        match index {
+            (true, index) => &mut self.shadow_scopes[index],
+            (false, index) => &mut self.scopes[index],
+        }
+    }
+
+    fn scope(&self, index: (bool, usize)) -> &Scope {
+        match index {
+            (true, index) => &self.shadow_scopes[index],
+            (false, index) => &self.scopes[index],
+        }
+    }
+
+    // the scope an assignment to this name ends up in
+    pub fn declaring_scope_mut(&mut self, name: &str) -> &mut Scope {
+        let item = self.identifier_item(name);
+        let index = self.scope_index(&item).unwrap();
+        self.scope_mut(index)
+    }
+
+    // names that get assigned in a function are never looked up elsewhere
+    pub fn is_local(&self, name: &str) -> bool {
+        self.scopes.len() > 2 && self.scopes.last().unwrap().is_local(name)
+    }
+
+    // the scopes to look for a name in, innermost first
+    pub fn lookup_scopes(&self, name: &str) -> Vec<&Scope> {
+        let item = self.identifier_item(name);
+
+        match item {
+            AnalysisItem::Identifier(_) if self.is_local(name) => self.scopes.iter().rev().take(1).collect(),
+            AnalysisItem::Identifier(_) => self.scopes.iter().rev().collect(),
+            _ => {
+                let index = self.scope_index(&item).unwrap();
+                let mut scopes = vec!(self.scope(index));
+                if index != (false, 1) {
+                    scopes.push(&self.scopes[1]);
+                }
+                scopes.push(&self.scopes[0]);
+                scopes
+            }
+        }
+    }
+
+    // the scopes that hold any of the changed identifiers
+    fn changed_scopes(&self, changes: &[&AnalysisItem]) -> BTreeSet<(bool, usize)> {
+        changes.iter().filter_map(|change| self.scope_index(change)).collect()
+    }
+
     pub fn pop_path(&mut self) {
         self.paths.pop();
     }
@@ -295,6 +418,64 @@ impl VirtualMachine {
         }
     }
 
+    // calls a function from inside the vm, like the function passed to map
+    pub fn call_function(&mut self,
+                         executors: &Executors,
+                         function: &Pointer,
+                         name: &str,
+                         args: Vec<Mapping>)
+                         -> ExecutionResult {
+        let frame = PathNode::Frame(self.current_node().clone(), Some(name.to_owned()), 0, 1);
+
+        let mut new_args = Vec::new();
+        for arg in args {
+            new_args.push(arg.augment(frame.clone()));
+        }
+
+        let mut current_path = self.current_path().clone();
+        current_path.add_node(frame.clone());
+        self.push_path(current_path);
+
+        self.new_result();
+
+        // only transfer the changes that outlive the call, like regular calls
+        let mut changes = Vec::new();
+        if let Some(body_result) = self.call(executors, function, new_args, vec!()) {
+            for item in body_result.changes.into_iter().chain(body_result.dependencies) {
+                if item.escapes_call() {
+                    changes.push(item);
+                }
+            }
+        }
+
+        let results = self.get_result();
+
+        self.pop_path();
+        self.next_branch(&changes);
+        self.merge_function(&changes);
+
+        let mut mapping = Mapping::new();
+
+        for (return_path, return_mapping) in results {
+            for (mut path, address) in return_mapping {
+                path.merge_into(return_path.clone());
+                path.add_node(frame.clone());
+                mapping.add_mapping(path, address);
+            }
+        }
+
+        if mapping.is_empty() {
+            mapping.add_mapping(Path::empty(), self.knowledge().constant("None"));
+        }
+
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: changes.clone(),
+            changes: changes,
+            result: mapping,
+        }
+    }
+
     pub fn index(&mut self,
                  executors: &Executors,
                  target: &GastNode,
@@ -371,15 +552,31 @@ impl VirtualMachine {
         }
     }
 
+    pub fn conditional_expression(&mut self,
+                                  executors: &Executors,
+                                  test: &GastNode,
+                                  body: &GastNode,
+                                  or_else: &GastNode)
+                                  -> ExecutionResult {
+        match executors.conditional_expression {
+            Some(ref conditional) => {
+                let env = Environment::new(self, executors);
+                conditional.execute(env, test, body, or_else)
+            }
+            None => panic!("VM is not setup to execute conditional expressions"),
+        }
+    }
+
     pub fn while_loop(&mut self,
                        executors: &Executors,
                        test: &GastNode,
-                       body: &GastNode)
+                       body: &GastNode,
+                       or_else: &GastNode)
                        -> ExecutionResult {
         match executors.while_loop {
             Some(ref while_loop) => {
                 let env = Environment::new(self, executors);
-                while_loop.execute(env, test, body)
+                while_loop.execute(env, test, body, or_else)
             }
             None => panic!("VM is not setup to execute while loops"),
         }
@@ -388,12 +585,13 @@ impl VirtualMachine {
     pub fn foreach(&mut self,
                        executors: &Executors,
                        before: &GastNode,
-                       body: &GastNode)
+                       body: &GastNode,
+                       or_else: &GastNode)
                        -> ExecutionResult {
         match executors.foreach {
             Some(ref foreach) => {
                 let env = Environment::new(self, executors);
-                foreach.execute(env, before, body)
+                foreach.execute(env, before, body, or_else)
             }
             None => panic!("VM is not setup to execute foreach loops"),
         }
@@ -483,14 +681,35 @@ impl VirtualMachine {
         }
     }
 
-    pub fn string(&mut self, executors: &Executors) -> ExecutionResult {
-        match executors.string {
+    pub fn string(&mut self, executors: &Executors, value: &str) -> ExecutionResult {
+        let result = match executors.string {
             Some(ref string) => {
                 let env = Environment::new(self, executors);
                 string.execute(env)
             }
             None => panic!("VM is not setup to execute strings"),
+        };
+
+        for &(_, ref address) in &result.result {
+            self.strings.insert(*address, value.to_owned());
         }
+
+        result
+    }
+
+    pub fn joined_string(&mut self, executors: &Executors, values: &[GastNode]) -> ExecutionResult {
+        match executors.joined_string {
+            Some(ref joined_string) => {
+                let env = Environment::new(self, executors);
+                joined_string.execute(env, values)
+            }
+            None => panic!("VM is not setup to execute f-strings"),
+        }
+    }
+
+    // the value of a string literal, if this is one
+    pub fn string_value(&self, address: &Pointer) -> Option<&String> {
+        self.strings.get(address)
     }
 
     pub fn negate(&mut self, executors: &Executors, content: &GastNode) -> ExecutionResult {
@@ -555,12 +774,13 @@ impl VirtualMachine {
     pub fn assign(&mut self,
                   executors: &Executors,
                   targets: &[GastNode],
-                  value: &GastNode)
+                  value: &GastNode,
+                  augmented: bool)
                   -> ExecutionResult {
         match executors.assign {
             Some(ref assign) => {
                 let env = Environment::new(self, executors);
-                assign.execute(env, targets, value)
+                assign.execute(env, targets, value, augmented)
             }
             None => panic!("VM is not setup to execute assignments"),
         }
@@ -580,6 +800,20 @@ impl VirtualMachine {
         }
     }
 
+    pub fn assign_target(&mut self,
+                  executors: &Executors,
+                  target: &GastNode,
+                  value: Mapping)
+                  -> ExecutionResult {
+        match executors.assign {
+            Some(ref assign) => {
+                let env = Environment::new(self, executors);
+                assign.to_target(env, target, value)
+            }
+            None => panic!("VM is not setup to execute assignments"),
+        }
+    }
+
     pub fn break_loop(&mut self, executors: &Executors) -> ExecutionResult {
         match executors.break_loop {
             Some(ref break_loop) => {
@@ -610,6 +844,36 @@ impl VirtualMachine {
         }
     }
 
+    pub fn global(&mut self, executors: &Executors, names: &[String]) -> ExecutionResult {
+        match executors.global {
+            Some(ref global) => {
+                let env = Environment::new(self, executors);
+                global.execute(env, names)
+            }
+            None => panic!("VM is not setup to execute global statements"),
+        }
+    }
+
+    pub fn nonlocal(&mut self, executors: &Executors, names: &[String]) -> ExecutionResult {
+        match executors.nonlocal {
+            Some(ref nonlocal) => {
+                let env = Environment::new(self, executors);
+                nonlocal.execute(env, names)
+            }
+            None => panic!("VM is not setup to execute nonlocal statements"),
+        }
+    }
+
+    pub fn delete(&mut self, executors: &Executors, targets: &[GastNode]) -> ExecutionResult {
+        match executors.delete {
+            Some(ref delete) => {
+                let env = Environment::new(self, executors);
+                delete.execute(env, targets)
+            }
+            None => panic!("VM is not setup to execute del statements"),
+        }
+    }
+
     pub fn unop(&mut self, executors: &Executors, value: &GastNode) -> ExecutionResult {
         match executors.unop {
             Some(ref unop) => {
@@ -660,11 +924,17 @@ impl VirtualMachine {
         }
     }
 
-    pub fn slice(&mut self, executors: &Executors, target: &GastNode, lower: &GastNode, upper: &GastNode) -> ExecutionResult {
+    pub fn slice(&mut self, 
+                 executors: &Executors, 
+                 target: &GastNode, 
+                 lower: &GastNode, 
+                 upper: &GastNode, 
+                 step: &GastNode) 
+                 -> ExecutionResult {
         match executors.slice {
             Some(ref slice) => {
                 let env = Environment::new(self, executors);
-                slice.execute(env, target, lower, upper)
+                slice.execute(env, target, lower, upper, step)
             }
             None => panic!("VM is not setup to execute slices"),
         }
@@ -690,7 +960,8 @@ impl VirtualMachine {
 
         let result = match *kind {
             NodeType::Boolean { ref value } => self.boolean(executors, *value),
-            NodeType::String { .. } => self.string(executors),
+            NodeType::String { ref value } => self.string(executors, value),
+            NodeType::JoinedStr { ref values } => self.joined_string(executors, values),
             NodeType::Int { .. } => self.int(executors),
             NodeType::Float { .. } => self.float(executors),
             NodeType::Nil {} => self.load_identifier(executors, &"None".to_owned()),
@@ -703,17 +974,20 @@ impl VirtualMachine {
             NodeType::If { ref test, ref body, ref or_else } => {
                 self.conditional(executors, test, body, or_else)
             }
+            NodeType::IfExp { ref test, ref body, ref or_else } => {
+                self.conditional_expression(executors, test, body, or_else)
+            }
             NodeType::Block { ref content } => self.block(executors, content),
             NodeType::Identifier { ref name } => self.load_identifier(executors, name),
             NodeType::Attribute { ref parent, ref attribute } => {
                 self.load_attribute(executors, parent, attribute)
             }
             NodeType::Declaration { ref id, ref kind } => self.declaration(executors, id, kind),
-            NodeType::Assignment { ref targets, ref value } => {
-                self.assign(executors, targets, value)
+            NodeType::Assignment { ref targets, ref value, augmented } => {
+                self.assign(executors, targets, value, augmented)
             }
-            NodeType::While { ref test, ref body } => {
-                self.while_loop(executors, test, body)
+            NodeType::While { ref test, ref body, ref or_else } => {
+                self.while_loop(executors, test, body, or_else)
             }
             NodeType::Break {  } => {
                 self.break_loop(executors)
@@ -721,6 +995,15 @@ impl VirtualMachine {
             NodeType::Continue {  } => {
                 self.continue_loop(executors)
             }
+            NodeType::Global { ref names } => {
+                self.global(executors, names)
+            }
+            NodeType::Nonlocal { ref names } => {
+                self.nonlocal(executors, names)
+            }
+            NodeType::Delete { ref targets } => {
+                self.delete(executors, targets)
+            }
             NodeType::List { ref content } => {
                 self.list(executors, content)
             }
@@ -748,8 +1031,8 @@ impl VirtualMachine {
             NodeType::AndThen {ref first, ref second} => {
                 self.andthen(executors, first, second)
             }
-            NodeType::ForEach {ref before, ref body} => {
-                self.foreach(executors, before, body)
+            NodeType::ForEach {ref before, ref body, ref or_else} => {
+                self.foreach(executors, before, body, or_else)
             }
             NodeType::Call {ref target, ref args, ref kwargs} => {
                 self._call(executors, target, args, kwargs)
@@ -763,8 +1046,8 @@ impl VirtualMachine {
             NodeType::UnOp {ref value, ..} => {
                 self.unop(executors, value)
             }
-            NodeType::Slice {ref target, ref lower, ref upper} => {
-                self.slice(executors, target, lower, upper)
+            NodeType::Slice {ref target, ref lower, ref upper, ref step} => {
+                self.slice(executors, target, lower, upper, step)
             }
             NodeType::FunctionDef {ref name, ref body, ref args, ref kw_args, ref vararg, ref kw_vararg} => {
                 self.function(executors, name, args, kw_args, vararg, kw_vararg, body)
@@ -784,82 +1067,70 @@ impl VirtualMachine {
     }
 
     pub fn next_branch(&mut self, changes: &[AnalysisItem]) {
-        let mut identifier_changed = false;
-
         let set: HashSet<_> = changes.iter().collect(); // dedup
         let changes: Vec<_> = set.into_iter().collect();
+        let scopes = self.changed_scopes(&changes);
 
         for change in changes {
             if let AnalysisItem::Object(ref address) = *change {
                 let mut object = self.memory.get_object_mut(address);
                 object.next_branch();
-            } else if let AnalysisItem::Identifier( _ ) = *change {
-                identifier_changed = true;
             }
         }
 
-        if identifier_changed {
-            self.scopes.last_mut().unwrap().next_branch();
+        for index in scopes {
+            self.scope_mut(index).next_branch();
         }
     }
 
     pub fn reset_branch_counter(&mut self, changes: &[AnalysisItem]) {
-        let mut identifier_changed = false;
-
         let set: HashSet<_> = changes.iter().collect(); // dedup
         let changes: Vec<_> = set.into_iter().collect();
+        let scopes = self.changed_scopes(&changes);
 
         for change in changes {
             if let AnalysisItem::Object(ref address) = *change {
                 let mut object = self.memory.get_object_mut(address);
                 object.reset_branch_counter();
-            } else if let AnalysisItem::Identifier( _ ) = *change {
-                identifier_changed = true;
             }
         }
 
-        if identifier_changed {
-            self.scopes.last_mut().unwrap().reset_branch_counter();
+        for index in scopes {
+            self.scope_mut(index).reset_branch_counter();
         }
     }
 
     pub fn merge_branches(&mut self, changes: &[AnalysisItem], hide_as_loop: Vec<Option<bool>>, restrictions: Vec<Vec<Path>>) {
-        let mut identifier_changed = false;
-
         let set: HashSet<_> = changes.iter().collect(); // dedup
         let changes: Vec<_> = set.into_iter().collect();
+        let scopes = self.changed_scopes(&changes);
 
         for change in changes {
             if let AnalysisItem::Object (ref address) = *change {
                 let mut object = self.memory.get_object_mut(address);
                 object.merge_branches(hide_as_loop.clone(), &restrictions);
-            } else if let AnalysisItem::Identifier ( _ ) = *change {
-                identifier_changed = true;
             }
         }
 
-        if identifier_changed {
-            self.scopes.last_mut().unwrap().merge_branches(hide_as_loop.clone(), &restrictions);
+        for index in scopes {
+            self.scope_mut(index).merge_branches(hide_as_loop.clone(), &restrictions);
         }
     }
 
     pub fn merge_loop(&mut self, changes: &[AnalysisItem]) {
-        let mut identifier_changed = false;
-
         let set: HashSet<_> = changes.iter().collect(); // dedup
         let changes: Vec<_> = set.into_iter().collect();
+        let scopes = self.changed_scopes(&changes);
 
         for change in changes {
             if let AnalysisItem::Object (ref address) = *change {
                 let mut object = self.memory.get_object_mut(address);
                 object.merge_loop();
-            } else if let AnalysisItem::Identifier ( _ ) = *change {
-                identifier_changed = true;
             }
         }
 
-        if identifier_changed {
-            self.scopes.last_mut().unwrap().merge_loop();
+        for index in scopes {
+            self.scope_mut(index).merge_loop();
         }
     }
 
@@ -868,6 +1139,7 @@ impl VirtualMachine {
         
         let set: HashSet<_> = changes.iter().collect(); // dedup
         let changes: Vec<_> = set.into_iter().collect();
+        let scopes = self.changed_scopes(&changes);
 
         for change in changes {
             if let AnalysisItem::Object (ref address) = *change {
@@ -878,6 +1150,14 @@ impl VirtualMachine {
             }
         }
 
+        // the local scope of the function is discarded as a whole
+        let local = (false, self.scopes.len().saturating_sub(1));
+        for index in scopes {
+            if !(identifier_changed && index == local) {
+                self.scope_mut(index).merge_function();
+            }
+        }
+
         if identifier_changed {
             self.scopes.pop().unwrap().discard_function();
         }
@@ -1028,6 +1308,22 @@ impl VirtualMachine {
         self.nodes.pop();
     }
 
+    pub fn record_types(&mut self, mapping: &Mapping) {
+        let node = *self.current_node().last().unwrap();
+        let mut types = BTreeSet::new();
+
+        for &(_, ref address) in mapping {
+            let object = self.memory.get_object(address);
+            types.insert(object.get_type_name(&self.knowledge_base));
+        }
+
+        self.inferred.entry(node).or_insert_with(BTreeSet::new).extend(types);
+    }
+
+    pub fn inferred_types(&self) -> &HashMap<GastID, BTreeSet<String>> {
+        &self.inferred
+    }
+
     pub fn knowledge(&self) -> &KnowledgeBase {
         &self.knowledge_base
     }
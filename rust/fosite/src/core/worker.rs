@@ -1,21 +1,48 @@
 use super::message::*;
 use super::CHANNEL;
 use std::thread::*;
+use std::sync::mpsc::Sender;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use super::GastID;
 use super::GastNode;
+use super::PathID;
+use super::Sarif;
+use super::Config;
+use super::Baseline;
+use super::fingerprint;
+use super::RULES;
+use super::rule_index;
+use super::lsp_diagnostic;
+
+use term_painter::ToStyle;
+use term_painter::Attr::*;
+
+use rustc_serialize::json::Json;
 
-type Sources = HashMap<GastID, (i16, i16)>;
 type Nodes = HashMap<GastID, GastNode>;
 
 pub struct Worker {
     thread: JoinHandle<()>,
 }
 
+pub enum Format {
+    Terminal,
+    Sarif(String),
+    Lsp(String, Sender<Json>),
+}
+
 impl Worker {
     pub fn new() -> Worker {
-        let mut logger = Logger::new();
+        Worker::with_format(Format::Terminal)
+    }
+
+    pub fn with_format(format: Format) -> Worker {
+        Worker::with_config(format, Config::new())
+    }
+
+    pub fn with_config(format: Format, config: Config) -> Worker {
+        let mut logger = Logger::new(format, config);
 
         let thread = {
             spawn(move || logger.message_loop())
@@ -45,14 +72,126 @@ struct Logger {
     nodes: Nodes,
     sources: Sources,
     done: HashSet<u64>,
+    sarif: Option<Sarif>,
+    lsp: Option<(String, Sender<Json>)>,
+
+    // filtering
+    config: Config,
+    suppressions: HashMap<GastID, (Vec<String>, Option<i16>)>,
+    hidden: HashSet<u64>,
+    counts: HashMap<i16, usize>,
+    baseline: Option<Baseline>,
+    new_baseline: Option<Baseline>,
+    reported: usize,
+    suppressed: usize,
+    disabled: usize,
+    limited: usize,
+    baselined: usize,
 }
 
 impl Logger {
-    fn new() -> Logger {
+    fn new(format: Format, mut config: Config) -> Logger {
+        let (sarif, lsp) = match format {
+            Format::Terminal => (None, None),
+            Format::Sarif(uri) => (Some(Sarif::new(uri)), None),
+            Format::Lsp(uri, diagnostics) => (None, Some((uri, diagnostics))),
+        };
+
+        let mut sources = Sources::new();
+
+        for (code, severity) in config.severities() {
+            sources.set_severity(*code, *severity);
+        }
+
+        let baseline = config.take_baseline();
+        let new_baseline = config.update_baseline().map(|_| Baseline::new());
+
         Logger {
-            sources: HashMap::new(),
+            sources: sources,
             nodes: HashMap::new(),
             done: HashSet::new(),
+            sarif: sarif,
+            lsp: lsp,
+            config: config,
+            suppressions: HashMap::new(),
+            hidden: HashSet::new(),
+            counts: HashMap::new(),
+            baseline: baseline,
+            new_baseline: new_baseline,
+            reported: 0,
+            suppressed: 0,
+            disabled: 0,
+            limited: 0,
+            baselined: 0,
+        }
+    }
+
+    // suppression comments apply to the statement and everything in it
+    fn is_suppressed(&self, code: i16, source: &PathID) -> bool {
+        let name = match rule_index(code) {
+            Some(index) => RULES[index].1,
+            None => return false,
+        };
+
+        let row = match source.last().and_then(|node| self.sources.get(node)) {
+            Some(&(row, _)) => row,
+            None => return false,
+        };
+
+        self.suppressions.iter().any(|(node, &(ref codes, last_row))| {
+            if !codes.iter().any(|c| c == "*" || c == name) {
+                return false;
+            }
+
+            if source.contains(node) {
+                return true;
+            }
+
+            match self.sources.get(node) {
+                Some(&(start, _)) => {
+                    start <= row && row <= last_row.unwrap_or(start)
+                }
+                None => false,
+            }
+        })
+    }
+
+    fn is_limited(&self, code: i16) -> bool {
+        if let Some(max) = self.config.max_messages() {
+            if self.reported >= max {
+                return true;
+            }
+        }
+
+        if let Some(max) = self.config.max_per_rule() {
+            if *self.counts.get(&code).unwrap_or(&0) >= max {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn print_summary(&self) {
+        if self.suppressed + self.disabled + self.limited + self.baselined == 0 {
+            return;
+        }
+
+        println!("{}",
+            Bold.paint(format!("{} reported, {} suppressed, {} disabled, {} over the limits, {} in the baseline",
+                self.reported,
+                self.suppressed,
+                self.disabled,
+                self.limited,
+                self.baselined)));
+    }
+
+    fn write_baseline(&self) {
+        if let (Some(path), Some(baseline)) = (self.config.update_baseline(), self.new_baseline.as_ref()) {
+            match baseline.save(path) {
+                Ok(_) => println!("Wrote {} messages to the baseline {}", baseline.len(), path),
+                Err(why) => eprintln!("{}", why),
+            }
         }
     }
 
@@ -60,18 +199,87 @@ impl Logger {
         for message in CHANNEL.iter() {
             match message {
                 Message::Output { ref source, ref content } => {
-                    if !self.done.contains(&content.hash(source)) {
-                        self.done.insert(content.hash(source));
-                        content.print_message(&self.sources, &self.nodes, source.clone());
+                    let hash = content.hash(source);
+
+                    if !self.done.contains(&hash) {
+                        // not every message applies to every node
+                        if content.summary(&self.nodes, source).is_none() {
+                            self.done.insert(hash);
+                            continue;
+                        }
+
+                        let code = content.code();
+
+                        if !self.config.is_enabled(code) {
+                            self.done.insert(hash);
+                            self.disabled += 1;
+                            continue;
+                        }
+
+                        // the same message can still be reported elsewhere
+                        if self.is_suppressed(code, source) {
+                            if self.hidden.insert(hash) {
+                                self.suppressed += 1;
+                            }
+                            continue;
+                        }
+
+                        self.done.insert(hash);
+
+                        let print = fingerprint(&**content, &self.sources, &self.nodes, source);
+
+                        if let Some(ref mut baseline) = self.new_baseline {
+                            baseline.add(code, hash, print);
+                            continue;
+                        }
+
+                        if let Some(ref mut baseline) = self.baseline {
+                            if baseline.take(code, hash, print) {
+                                self.baselined += 1;
+                                continue;
+                            }
+                        }
+
+                        if self.is_limited(code) {
+                            self.limited += 1;
+                            continue;
+                        }
+
+                        *self.counts.entry(code).or_insert(0) += 1;
+                        self.reported += 1;
+
+                        if let Some(ref mut sarif) = self.sarif {
+                            sarif.add(&**content, hash, &self.sources, &self.nodes, source);
+                        } else if let Some((ref uri, ref diagnostics)) = self.lsp {
+                            if let Some(diagnostic) = lsp_diagnostic(&**content, uri, &self.sources, &self.nodes, source) {
+                                let _ = diagnostics.send(diagnostic);
+                            }
+                        } else {
+                            content.print_message(&self.sources, &self.nodes, source.clone());
+                        }
                     }
                 }
-                Message::Input { source, line, col, node } => {
+                Message::Input { source, line, col, end, node } => {
                     // println!("mapping node {} to ({}, {})", source, line, col);
-                    self.sources.insert(source, (line, col));
+                    self.sources.insert(source, (line, col), end);
                     self.nodes.insert(source, node);
                 }
+                Message::Source { ref text } => {
+                    self.sources.set_text(text);
+                }
+                Message::Suppress { source, codes, last_row } => {
+                    self.suppressions.insert(source, (codes, last_row));
+                }
                 Message::Terminate => break,
             }
         }
+
+        if self.new_baseline.is_some() {
+            self.write_baseline();
+        } else if let Some(ref sarif) = self.sarif {
+            println!("{}", sarif.to_json().pretty());
+        } else if self.lsp.is_none() {
+            self.print_summary();
+        }
     }
 }
\ No newline at end of file
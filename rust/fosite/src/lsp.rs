@@ -0,0 +1,303 @@
+// Language Server Protocol mode, JSON-RPC over stdio
+//
+// Documents are converted to GAST by the python frontend (py.py), the
+// analysis itself runs in process. FOSITE_PYTHON and FOSITE_FRONTEND
+// override the interpreter and the location of py.py.
+
+use rustc_serialize::json::Json;
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::prelude::*;
+use std::panic;
+use std::process::Command;
+use std::sync::mpsc::channel;
+
+use core::GastID;
+use core::Worker;
+use core::Format;
+use core::Config;
+use core::lsp_range;
+
+use super::analyse;
+
+// identifiers in the document: (row, col, length, node)
+type Identifiers = Vec<(i16, i16, i16, GastID)>;
+
+struct Document {
+    identifiers: Identifiers,
+    types: HashMap<GastID, BTreeSet<String>>,
+}
+
+pub fn serve() {
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut documents: HashMap<String, Document> = HashMap::new();
+
+    while let Some(request) = read_message(&mut input) {
+        let method = request.find("method").and_then(|m| m.as_string()).unwrap_or("").to_owned();
+        let id = request.find("id").cloned();
+        let params = request.find("params").cloned().unwrap_or(Json::Null);
+
+        match method.as_str() {
+            "initialize" => {
+                let mut capabilities = BTreeMap::new();
+                capabilities.insert("textDocumentSync".to_owned(), Json::I64(1));
+                capabilities.insert("hoverProvider".to_owned(), Json::Boolean(true));
+
+                let mut info = BTreeMap::new();
+                info.insert("name".to_owned(), Json::String("fosite".to_owned()));
+                info.insert("version".to_owned(), Json::String(env!("CARGO_PKG_VERSION").to_owned()));
+
+                let mut result = BTreeMap::new();
+                result.insert("capabilities".to_owned(), Json::Object(capabilities));
+                result.insert("serverInfo".to_owned(), Json::Object(info));
+                respond(id, Json::Object(result));
+            }
+            "textDocument/didOpen" => {
+                let uri = string_at(&params, &["textDocument", "uri"]);
+                let text = string_at(&params, &["textDocument", "text"]);
+
+                if let (Some(uri), Some(text)) = (uri, text) {
+                    update(&mut documents, uri, &text);
+                }
+            }
+            "textDocument/didChange" => {
+                let uri = string_at(&params, &["textDocument", "uri"]);
+                let text = params.find("contentChanges")
+                    .and_then(|changes| changes.as_array())
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.find("text"))
+                    .and_then(|text| text.as_string())
+                    .map(|text| text.to_owned());
+
+                if let (Some(uri), Some(text)) = (uri, text) {
+                    update(&mut documents, uri, &text);
+                }
+            }
+            "textDocument/didClose" => {
+                if let Some(uri) = string_at(&params, &["textDocument", "uri"]) {
+                    documents.remove(&uri);
+                    publish(&uri, Vec::new());
+                }
+            }
+            "textDocument/hover" => {
+                let uri = string_at(&params, &["textDocument", "uri"]).unwrap_or_default();
+                let line = params.find_path(&["position", "line"]).and_then(|l| l.as_i64());
+                let character = params.find_path(&["position", "character"]).and_then(|c| c.as_i64());
+
+                let result = match (documents.get(&uri), line, character) {
+                    (Some(document), Some(line), Some(character)) => {
+                        hover(document, line as i16 + 1, character as i16)
+                    }
+                    _ => Json::Null,
+                };
+
+                respond(id, result);
+            }
+            "shutdown" => respond(id, Json::Null),
+            "exit" => break,
+            _ => {
+                // notifications don't get a response
+                if let Some(id) = id {
+                    let mut error = BTreeMap::new();
+                    error.insert("code".to_owned(), Json::I64(-32601));
+                    error.insert("message".to_owned(), Json::String(format!("unsupported method {}", method)));
+
+                    let mut response = BTreeMap::new();
+                    response.insert("jsonrpc".to_owned(), Json::String("2.0".to_owned()));
+                    response.insert("id".to_owned(), id);
+                    response.insert("error".to_owned(), Json::Object(error));
+                    write_message(&Json::Object(response));
+                }
+            }
+        }
+    }
+}
+
+fn update(documents: &mut HashMap<String, Document>, uri: String, text: &str) {
+    let json = match frontend(text) {
+        Ok(json) => json,
+        Err(why) => {
+            log(&format!("fosite could not convert {}: {}", uri, why));
+            return;
+        }
+    };
+
+    let config = Config::load("fosite.toml").unwrap_or_else(|why| {
+        log(&why);
+        Config::new()
+    });
+
+    let (tx, rx) = channel();
+    let worker = Worker::with_config(Format::Lsp(uri.clone(), tx), config);
+    let vm = panic::catch_unwind(|| analyse(&json, &[]));
+    let _ = worker.finalize();
+
+    let diagnostics = rx.iter().collect();
+    publish(&uri, diagnostics);
+
+    let mut identifiers = Vec::new();
+    collect_identifiers(&json, &mut identifiers);
+
+    let types = match vm {
+        Ok(vm) => vm.inferred_types().clone(),
+        Err(_) => {
+            log(&format!("fosite could not analyse {}", uri));
+            HashMap::new()
+        }
+    };
+
+    documents.insert(uri, Document {
+        identifiers,
+        types,
+    });
+}
+
+fn frontend(text: &str) -> Result<Json, String> {
+    let python = env::var("FOSITE_PYTHON").unwrap_or_else(|_| "python".to_owned());
+    let script = env::var("FOSITE_FRONTEND").unwrap_or_else(|_| "py.py".to_owned());
+
+    let path = env::temp_dir().join(format!("fosite-lsp-{}.py", ::std::process::id()));
+
+    let output = File::create(&path)
+        .and_then(|mut file| file.write_all(text.as_bytes()))
+        .and_then(|_| Command::new(python).arg(script).arg(&path).output());
+
+    // the copy of the document is only needed by the frontend
+    let _ = fs::remove_file(&path);
+
+    let output = output.map_err(|why| format!("{}", why))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Json::from_str(&stdout).map_err(|why| format!("{}", why))
+}
+
+fn collect_identifiers(node: &Json, identifiers: &mut Identifiers) {
+    match *node {
+        Json::Object(ref obj) => {
+            if obj.get("kind").and_then(|k| k.as_string()) == Some("identifier") {
+                let id = obj.get("id").and_then(|i| i.as_i64());
+                let line = obj.get("line").and_then(|l| l.as_i64());
+                let col = obj.get("col").and_then(|c| c.as_i64());
+                let name = obj.get("name").and_then(|n| n.as_string());
+
+                if let (Some(id), Some(line), Some(col), Some(name)) = (id, line, col, name) {
+                    identifiers.push((line as i16, col as i16, name.len() as i16, id as GastID));
+                }
+            }
+
+            for value in obj.values() {
+                collect_identifiers(value, identifiers);
+            }
+        }
+        Json::Array(ref content) => {
+            for value in content {
+                collect_identifiers(value, identifiers);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn hover(document: &Document, row: i16, col: i16) -> Json {
+    for &(line, start, length, id) in &document.identifiers {
+        if line != row || col < start || col >= start + length {
+            continue;
+        }
+
+        if let Some(types) = document.types.get(&id) {
+            let types: Vec<String> = types.iter().cloned().collect();
+
+            let mut contents = BTreeMap::new();
+            contents.insert("kind".to_owned(), Json::String("markdown".to_owned()));
+            contents.insert("value".to_owned(), Json::String(format!("`{}`", types.join(" | "))));
+
+            let mut result = BTreeMap::new();
+            result.insert("contents".to_owned(), Json::Object(contents));
+            result.insert("range".to_owned(), lsp_range((line, start), (line, start + length)));
+            return Json::Object(result);
+        }
+    }
+
+    Json::Null
+}
+
+fn string_at(json: &Json, keys: &[&str]) -> Option<String> {
+    json.find_path(keys).and_then(|value| value.as_string()).map(|value| value.to_owned())
+}
+
+fn publish(uri: &str, diagnostics: Vec<Json>) {
+    let mut params = BTreeMap::new();
+    params.insert("uri".to_owned(), Json::String(uri.to_owned()));
+    params.insert("diagnostics".to_owned(), Json::Array(diagnostics));
+    notify("textDocument/publishDiagnostics", Json::Object(params));
+}
+
+fn log(text: &str) {
+    let mut params = BTreeMap::new();
+    params.insert("type".to_owned(), Json::I64(3));
+    params.insert("message".to_owned(), Json::String(text.to_owned()));
+    notify("window/logMessage", Json::Object(params));
+}
+
+fn notify(method: &str, params: Json) {
+    let mut message = BTreeMap::new();
+    message.insert("jsonrpc".to_owned(), Json::String("2.0".to_owned()));
+    message.insert("method".to_owned(), Json::String(method.to_owned()));
+    message.insert("params".to_owned(), params);
+    write_message(&Json::Object(message));
+}
+
+fn respond(id: Option<Json>, result: Json) {
+    let mut message = BTreeMap::new();
+    message.insert("jsonrpc".to_owned(), Json::String("2.0".to_owned()));
+    message.insert("id".to_owned(), id.unwrap_or(Json::Null));
+    message.insert("result".to_owned(), result);
+    write_message(&Json::Object(message));
+}
+
+fn read_message<R: BufRead>(input: &mut R) -> Option<Json> {
+    let mut length = None;
+
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header).unwrap_or(0) == 0 {
+            return None;
+        }
+
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+
+        if header.to_lowercase().starts_with("content-length:") {
+            length = header["content-length:".len()..].trim().parse::<usize>().ok();
+        }
+    }
+
+    let mut content = vec![0; length.unwrap_or(0)];
+    if input.read_exact(&mut content).is_err() {
+        return None;
+    }
+
+    // malformed messages are ignored, not fatal
+    Some(Json::from_str(&String::from_utf8_lossy(&content)).unwrap_or(Json::Null))
+}
+
+fn write_message(message: &Json) {
+    let content = message.to_string();
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+    let _ = write!(output, "Content-Length: {}\r\n\r\n{}", content.len(), content);
+    let _ = output.flush();
+}
@@ -7,10 +7,12 @@ extern crate bidir_map;
 extern crate term_painter;
 
 extern crate rustc_serialize;
+extern crate toml;
 use rustc_serialize::json::Json;
 
 pub mod core;
 pub mod python;
+mod lsp;
 
 pub use core::VirtualMachine;
 use core::build;
@@ -18,6 +20,9 @@ use core::build;
 use std::io::prelude::*;
 use std::fs::File;
 use core::Worker;
+use core::Format;
+use core::Config;
+use core::Baseline;
 use core::Executors;
 
 use python::*;
@@ -29,15 +34,101 @@ pub type Pointer = i16;
 type TypePointer = i16;
 
 fn main() {
-    let worker = Worker::new();
-    test_vm();
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.get(1).map(|arg| arg == "lsp").unwrap_or(false) {
+        lsp::serve();
+        return;
+    }
+
+    // --config path: rule configuration, fosite.toml by default
+    let path = argument(&args, "--config").unwrap_or("fosite.toml".to_owned());
+    let mut config = match Config::load(&path) {
+        Ok(config) => config,
+        Err(why) => {
+            eprintln!("{}", why);
+            std::process::exit(1);
+        }
+    };
+
+    // --baseline path: messages in this file aren't reported
+    // --update-baseline: write the current messages to it instead
+    let baseline = argument(&args, "--baseline")
+        .or(config.baseline_file().cloned())
+        .unwrap_or("fosite-baseline.json".to_owned());
+
+    if args.iter().any(|arg| arg == "--update-baseline") {
+        config.set_update_baseline(baseline);
+    } else if argument(&args, "--baseline").is_some() || std::path::Path::new(&baseline).exists() {
+        match Baseline::load(&baseline) {
+            Ok(baseline) => config.set_baseline(baseline),
+            Err(why) => {
+                eprintln!("{}", why);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // --sarif [uri]: report in SARIF, uri names the analysed source file
+    let format = if args.iter().any(|arg| arg == "--sarif") {
+        Format::Sarif(argument(&args, "--sarif").unwrap_or("input.py".to_owned()))
+    } else {
+        Format::Terminal
+    };
+
+    // --stubs path: signatures of extra modules, functions and methods
+    let mut stubs = Vec::new();
+
+    for (index, arg) in args.iter().enumerate() {
+        if arg != "--stubs" {
+            continue;
+        }
+
+        let path = args.get(index + 1).cloned().unwrap_or_default();
+        let mut text = String::new();
+
+        match File::open(&path).and_then(|mut file| file.read_to_string(&mut text)) {
+            Ok(_) => stubs.push(text),
+            Err(why) => {
+                eprintln!("{}: {}", path, why);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let worker = Worker::with_config(format, config);
+
+    test_vm(&stubs);
     let _ = worker.finalize();
     // test_collection();
 }
 
+// the value following a command line option
+fn argument(args: &[String], name: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|index| args.get(index + 1))
+        .filter(|value| !value.starts_with("--"))
+        .cloned()
+}
+
+
+
+fn test_vm(stubs: &[String]) {
+    let mut s = String::new();
+
+    let _ = match File::open("input.json") {
+        Ok(mut file) => file.read_to_string(&mut s),
+        Err(why) => panic!("{:?}", why),
+    };
 
+    let json = Json::from_str(&s).unwrap();
+    analyse(&json, stubs);
+}
 
-fn test_vm() {
+// runs the analysis on a GAST tree, messages are published on the channel
+// stubs are extra signatures, on top of the builtin catalogue
+fn analyse(json: &Json, stubs: &[String]) -> VirtualMachine {
     let executors = Executors {
         assign: Some(Box::new(PythonAssign {})),
         attribute: Some(Box::new(PythonAttribute {})),
@@ -46,14 +137,19 @@ fn test_vm() {
         block: Some(Box::new(PythonBlock {})),
         boolean: Some(Box::new(PythonBoolean {})),
         conditional: Some(Box::new(PythonConditional {})),
+        conditional_expression: Some(Box::new(PythonConditionalExpression {})),
         declaration: None,
         float: Some(Box::new(PythonFloat {})),
         identifier: Some(Box::new(PythonIdentifier {})),
         int: Some(Box::new(PythonInt {})),
         string: Some(Box::new(PythonString {})),
+        joined_string: Some(Box::new(PythonJoinedStr {})),
         while_loop: Some(Box::new(PythonWhile {})),
         break_loop: Some(Box::new(PythonBreak {})),
         continue_loop: Some(Box::new(PythonContinue {})),
+        global: Some(Box::new(PythonGlobal {})),
+        nonlocal: Some(Box::new(PythonNonlocal {})),
+        delete: Some(Box::new(PythonDelete {})),
         list: Some(Box::new(PythonList {})),
         sequence: Some(Box::new(PythonTuple {})),
         index: Some(Box::new(PythonIndex {})),
@@ -74,15 +170,7 @@ fn test_vm() {
         ret: Some(Box::new(PythonReturn {})),
     };
 
-    let mut s = String::new();
-
-    let _ = match File::open("input.json") {
-        Ok(mut file) => file.read_to_string(&mut s),
-        Err(why) => panic!("{:?}", why),
-    };
-
-    let json = Json::from_str(&s).unwrap();
-    let stuff = build(&json);
+    let stuff = build(json);
 
     let mut vm = VirtualMachine::new();
 
@@ -115,6 +203,7 @@ fn test_vm() {
     vm.declare_sub_type(&executors, "collection", "object");
 
     vm.declare_sub_type(&executors, "set", "collection");
+    vm.declare_sub_type(&executors, "frozenset", "collection");
     vm.declare_sub_type(&executors, "dict", "collection");
 
     // sequences have are ordered
@@ -131,23 +220,23 @@ fn test_vm() {
 
     {
         let mut kb = vm.knowledge_base();
-        kb.add_arithmetic_type("number", "+");
-        kb.add_arithmetic_type("number", "-");
-        kb.add_arithmetic_type("number", "/");
-        kb.add_arithmetic_type("number", "*");
-        kb.add_arithmetic_type("number", "//");
-        kb.add_arithmetic_type("number", "**");
-        kb.add_arithmetic_type("number", "%");
+
+        // (left, op, right) -> result
+        for op in &["+", "-", "*", "/", "//", "**", "%"] {
+            kb.add_operation("number", op, "number", "float");
+        }
 
         // ints have their own implementation
         // avoid coercion to float/number
-        kb.add_arithmetic_type("int", "+");
-        kb.add_arithmetic_type("int", "-");
-        //kb.add_arithmetic_type("int", "/");
-        kb.add_arithmetic_type("int", "*");
-        kb.add_arithmetic_type("int", "//");
-        kb.add_arithmetic_type("int", "**");
-        kb.add_arithmetic_type("int", "%");
+        for op in &["+", "-", "*", "//", "**", "%", "<<", ">>", "&", "|", "^"] {
+            kb.add_operation("int", op, "int", "int");
+        }
+
+        kb.add_operation("int", "/", "int", "float");
+
+        for op in &["&", "|", "^"] {
+            kb.add_operation("bool", op, "bool", "bool");
+        }
 
         kb.add_arithmetic_type("bool", "or");
         kb.add_arithmetic_type("bool", "and");
@@ -181,26 +270,57 @@ fn test_vm() {
         kb.add_arithmetic_type("collection", "in");
         kb.add_arithmetic_type("collection", "not in");
 
-        kb.add_arithmetic_type("list", "+");
-        kb.add_arithmetic_type("tuple", "+");
-        kb.add_arithmetic_type("str", "+");
+        kb.add_operation("str", "+", "str", "str");
+        kb.add_operation("list", "+", "list", "list");
+        kb.add_operation("tuple", "+", "tuple", "tuple");
+
+        // repetition works both ways, 3 * "a" uses str.__rmul__
+        for sequence in &["str", "list", "tuple"] {
+            kb.add_operation(sequence, "*", "int", sequence);
+            kb.add_reflected_operation(sequence, "*", "int", sequence);
+        }
 
-        kb.add_arithmetic_type("set", "-");
+        // the result has the type of the left operand
+        for op in &["-", "|", "&", "^"] {
+            kb.add_operation("set", op, "set", "set");
+            kb.add_operation("set", op, "frozenset", "set");
+            kb.add_operation("frozenset", op, "frozenset", "frozenset");
+            kb.add_operation("frozenset", op, "set", "frozenset");
+        }
+
+        kb.add_operation("dict", "|", "dict", "dict");
+
+        // printf-style formatting takes a single value or a tuple of values
+        kb.add_operation("str", "%", "object", "str");
     }
 
     define_modules(&mut vm);
 
+    if let Err(why) = load_stubs(&mut vm, CATALOGUE) {
+        panic!("invalid stub catalogue: {}", why);
+    }
+
+    for text in stubs {
+        if let Err(why) = load_stubs(&mut vm, text) {
+            panic!("invalid stubs: {}", why);
+        }
+    }
+
     // load builtin functions
     vm.import(&executors, "builtin", &[], &None);
 
     // load methods
     vm.import(&executors, "str", &[], &Some("str".to_owned()));
     vm.import(&executors, "list", &[], &Some("list".to_owned()));
+    vm.import(&executors, "dict", &[], &Some("dict".to_owned()));
+    vm.import(&executors, "set", &[], &Some("set".to_owned()));
+    vm.import(&executors, "frozenset", &[], &Some("frozenset".to_owned()));
 
     // global scope
     vm.new_scope();
 
     vm.execute(&executors, &stuff);
+    vm
 }
 
 fn define_modules(vm: &mut VirtualMachine) {
@@ -216,6 +336,15 @@ fn define_modules(vm: &mut VirtualMachine) {
     let list = new_list_module();
     vm.insert_module("list".to_owned(), list);
 
+    let dict = new_dict_module();
+    vm.insert_module("dict".to_owned(), dict);
+
+    let set = new_set_module();
+    vm.insert_module("set".to_owned(), set);
+
+    let frozenset = new_frozenset_module();
+    vm.insert_module("frozenset".to_owned(), frozenset);
+
     let cmath = new_cmath_module();
     vm.insert_module("cmath".to_owned(), cmath);
 }
\ No newline at end of file
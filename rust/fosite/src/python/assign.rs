@@ -1,5 +1,12 @@
 use core::*;
 
+use super::modules::constant_key;
+use super::modules::insert_key;
+use super::modules::check_hashable;
+use super::modules::collection_types;
+use super::modules::check_new_types;
+use super::binary_operation;
+
 use std::collections::btree_map::Entry;
 use std::collections::BTreeMap;
 
@@ -9,11 +16,16 @@ impl AssignExecutor for PythonAssign {
     fn execute(&self,
                env: Environment,
                targets: &[GastNode],
-               value: &GastNode)
+               value: &GastNode,
+               augmented: bool)
                -> ExecutionResult {
         let Environment { vm, executors } = env;
 
-        let value_execution = vm.execute(executors, value);
+        let value_execution = if augmented {
+            self.augmented_value(vm, executors, value)
+        } else {
+            vm.execute(executors, value)
+        };
 
         let mut total_changes = Vec::new();
         let mut total_dependencies = Vec::new();
@@ -80,9 +92,73 @@ impl AssignExecutor for PythonAssign {
             result: mapping,
         }
     }
+
+    fn to_target(&self,
+               env: Environment,
+               target: &GastNode,
+               value: Mapping)
+               -> ExecutionResult {
+        let Environment { vm, executors } = env;
+
+        let mut value_mapping = OptionalMapping::new();
+
+        for (path, address) in value {
+            value_mapping.add_mapping(path, Some(address));
+        }
+
+        self.assign_to_target(vm, executors, target, &value_mapping)
+    }
 }
 
 impl PythonAssign {
+    // `xs += ys` extends xs, the target gets bound to the same list again
+    fn augmented_value(&self,
+                       vm: &mut VirtualMachine,
+                       executors: &Executors,
+                       value: &GastNode)
+                       -> ExecutionResult {
+        let (left, op, right) = match value.kind {
+            NodeType::BinOp { ref left, ref op, ref right, .. } => (left, op, right),
+            _ => return vm.execute(executors, value),
+        };
+
+        let mut total_changes = Vec::new();
+        let mut total_dependencies = Vec::new();
+
+        let mut left_result = vm.execute(executors, left);
+        total_changes.append(&mut left_result.changes);
+        total_dependencies.append(&mut left_result.dependencies);
+
+        let mut right_result = vm.execute(executors, right);
+        total_changes.append(&mut right_result.changes);
+        total_dependencies.append(&mut right_result.dependencies);
+
+        // extended lists should keep a single type, like when appending
+        let mut lists = Mapping::new();
+        if op == "+" {
+            for &(ref path, ref address) in &left_result.result {
+                if vm.is_instance(address, "list") {
+                    lists.add_mapping(path.clone(), *address);
+                }
+            }
+        }
+
+        let original_types = collection_types(vm, &lists);
+
+        let mut result = binary_operation(vm, &left_result.result, op, &right_result.result, true);
+        total_changes.append(&mut result.changes);
+        total_dependencies.append(&mut result.dependencies);
+
+        check_new_types(vm, &left.to_string(), &lists, original_types);
+
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: total_dependencies,
+            changes: total_changes,
+            result: result.result,
+        }
+    }
+
     fn assign_to_target(&self,
                         vm: &mut VirtualMachine,
                         executors: &Executors,
@@ -209,7 +285,7 @@ impl PythonAssign {
             {
                 let object = vm.get_object(&address);
                 // one way to fix an off-by-one error
-                elements = object.slice_elements(left, right);
+                elements = object.slice_elements(left, right, Some(1));
             }
 
             let type_name = "list".to_owned();
@@ -496,7 +572,11 @@ impl PythonAssign {
                     vm.store_object_change(*target_address, &new_path);
 
                     // todo, pass on the new_path
+                    check_hashable(vm, &target.to_string(), &index_mapping);
+
                     self.insert_dictionary(vm, target_address, &index_mapping, &mapping);
+                    let current_path = vm.current_path().clone();
+                    insert_key(vm, target_address, constant_key(index), current_path);
                 }
                 else {
                     let kb = vm.knowledge();
@@ -601,7 +681,7 @@ impl PythonAssign {
                             target: &str,
                             mapping: &OptionalMapping)
                             -> ExecutionResult {
-        let changes = vec![AnalysisItem::Identifier (target.to_owned())];
+        let changes = vec![vm.identifier_item(target)];
 
         // todo get rid of clone
         let mapping = mapping.clone()
@@ -621,7 +701,7 @@ impl PythonAssign {
         
 
         {
-            let mut scope = vm.last_scope_mut();
+            let mut scope = vm.declaring_scope_mut(target);
             scope.set_optional_mapping(target.to_owned(), path, mapping.clone());
         }
 
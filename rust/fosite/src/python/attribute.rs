@@ -9,25 +9,41 @@ impl AttributeExecutor for PythonAttribute {
         let Environment { vm, executors } = env;
 
         let parent_result = vm.execute(executors, parent);
+        let mut result = self.attribute_mapping(vm, executors, parent, &parent_result.result, name);
 
+        let mut total_dependencies = parent_result.dependencies;
+        let mut total_changes = parent_result.changes;
+        total_dependencies.append(&mut result.dependencies);
+        total_changes.append(&mut result.changes);
+
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: total_dependencies,
+            changes: total_changes,
+            result: result.result,
+        }
+    }
+}
+
+impl PythonAttribute {
+    // load the attribute of a parent that has been evaluated already
+    pub fn attribute_mapping(&self,
+                             vm: &mut VirtualMachine,
+                             executors: &Executors,
+                             parent: &GastNode,
+                             parent_mapping: &Mapping,
+                             name: &str)
+                             -> ExecutionResult {
         let mut total_dependencies = Vec::new();
-        let mut total_changes = Vec::new();
         let mut mapping = Mapping::new();
 
         // which assumptions still need a valid mapping
         let mut unresolved = BTreeSet::new();
 
-        let parent_mapping = parent_result.result;
-        let mut dependencies = parent_result.dependencies;
-        let mut changes = parent_result.changes;
-
-        total_dependencies.append(&mut dependencies);
-        total_changes.append(&mut changes);
-
         let mut warning = BTreeSet::new();
         let mut error = BTreeSet::new();
 
-        for &(ref parent_path, ref parent_address) in &parent_mapping {
+        for &(ref parent_path, ref parent_address) in parent_mapping {
             total_dependencies.push(AnalysisItem::Object(*parent_address));
 
             let opt_mappings;
@@ -75,7 +91,7 @@ impl AttributeExecutor for PythonAttribute {
 
             // look for the attribute in its types
             if !unresolved.is_empty() {
-                if !types.is_empty() {
+                if types.is_empty() {
                     for unmet in &unresolved {
                         // todo, add type information as well
                         error.insert(unmet.clone());
@@ -139,19 +155,17 @@ impl AttributeExecutor for PythonAttribute {
             vm.store_identifier_dependency(AnalysisItem::Attribute (
                 Box::new(item),
                 name.to_owned(),
-            ), &parent_mapping);
+            ), parent_mapping);
         }
 
         ExecutionResult {
             flow: FlowControl::Continue,
             dependencies: total_dependencies,
-            changes: total_changes,
+            changes: vec!(),
             result: mapping,
         }
     }
-}
 
-impl PythonAttribute {
     fn load_object_attribute(&self,
                              vm: &VirtualMachine,
                              executors: &Executors,
@@ -1,5 +1,11 @@
 use core::*;
 
+use super::modules::collection_chunk;
+use super::modules::new_dict;
+use super::modules::update_dict;
+use super::modules::optional_elements;
+use super::modules::placeholder_count;
+
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::collections::btree_map::Entry;
@@ -15,160 +21,287 @@ impl BinOpExecutor for PythonBinOp {
                -> ExecutionResult {
         let mut total_changes = Vec::new();
         let mut total_dependencies = Vec::new();
-        let mut result = Mapping::new();
 
         let Environment { vm, executors } = env;
 
         let mut left_result = vm.execute(executors, left);
-        let left_mapping = left_result.result;
         total_changes.append(&mut left_result.changes);
         total_dependencies.append(&mut left_result.dependencies);
 
         let mut right_result = vm.execute(executors, right);
-        let right_mapping = right_result.result;
         total_changes.append(&mut right_result.changes);
         total_dependencies.append(&mut right_result.dependencies);
 
-        let mut error = BTreeMap::new();
+        let mut result = binary_operation(vm, &left_result.result, op, &right_result.result, false);
+        total_changes.append(&mut result.changes);
+        total_dependencies.append(&mut result.dependencies);
 
-        for &(ref left_path, ref left_address) in &left_mapping {
-            for &(ref right_path, ref right_address) in &right_mapping {
-                if !left_path.mergeable(right_path) {
-                    continue;
-                }
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: total_dependencies,
+            changes: total_changes,
+            result: result.result,
+        }
+    }
+}
 
-                let mut new_path = left_path.clone();
+// apply the operator to every combination of operands
+// in place, lists, sets and dicts on the left are changed rather than replaced
+pub fn binary_operation(vm: &mut VirtualMachine,
+                        left_mapping: &Mapping,
+                        op: &str,
+                        right_mapping: &Mapping,
+                        in_place: bool)
+                        -> ExecutionResult {
+    let mut total_changes = Vec::new();
+    let mut result = Mapping::new();
 
-                // todo can probably avoid this clone
-                new_path.merge_into(right_path.clone());
+    let mut error = BTreeMap::new();
+    let mut format_errors = BTreeMap::new();
 
-                // todo, bit of a hack
-                // concludes that if the most recently defined type supports addition
-                // that the entire thing does
-                // reality is more complicated, and full of runtime type checks
-                let mut ancestor_name = "None".to_owned();
+    for &(ref left_path, ref left_address) in left_mapping {
+        for &(ref right_path, ref right_address) in right_mapping {
+            if !left_path.mergeable(right_path) {
+                continue;
+            }
+
+            let mut new_path = left_path.clone();
+
+            // todo can probably avoid this clone
+            new_path.merge_into(right_path.clone());
+
+            let operation = operation_result(vm, left_address, op, right_address);
+
+            // lists are extended in place by any collection
+            let extends = in_place && op == "+" &&
+                vm.is_instance(left_address, "list") &&
+                vm.is_instance(right_address, "collection");
 
-                for ancestor in vm.common_ancestor(left_address, right_address).iter().rev() {
-                    ancestor_name = vm.knowledge().get_type_name(ancestor).clone();
-                    if vm.knowledge().operation_supported(&ancestor_name, op) {
-                        break;
-                    }
+            if op == "%" && operation.is_some() {
+                for (format, problem, path) in format_problems(vm, left_address, right_address) {
+                    let mut problem_path = new_path.clone();
+                    problem_path.merge_into(path);
+                    format_errors.entry((format, problem)).or_insert_with(Vec::new).push(problem_path);
                 }
+            }
+
+            if in_place && (extends || operation.is_some()) {
+                let mut change_path = vm.current_path().clone();
+                change_path.merge_into(left_path.clone());
 
-                // multiplying sequences with an integer is an annoying special case
-                let tuple_type = *vm.knowledge().get_type(&"tuple".to_owned()).unwrap();  
-                let list_type = *vm.knowledge().get_type(&"list".to_owned()).unwrap(); 
-                let string_type = *vm.knowledge().get_type(&"str".to_owned()).unwrap(); 
-                let int_type = *vm.knowledge().get_type(&"int".to_owned()).unwrap();
-
-                let left_ancestors = vm.ancestors(left_address);
-                let right_ancestors = vm.ancestors(right_address);
-
-                let b1 = left_ancestors.contains(&tuple_type) 
-                    || left_ancestors.contains(&list_type)
-                    || left_ancestors.contains(&string_type);
-                let b2 = right_ancestors.contains(&tuple_type) 
-                    || right_ancestors.contains(&list_type)
-                    || right_ancestors.contains(&string_type); 
-                let b3 = right_ancestors.contains(&int_type);
-                let b4 = left_ancestors.contains(&int_type);         
-
-                // normal multiplication 
-                if vm.knowledge().operation_supported(&ancestor_name, op) {
-                    let new_type = match &ancestor_name[..] {
-                        "number" => "float".to_owned(),
-                        _ => ancestor_name,
-                    };
-
-                    let mut new_ptr = vm.object_of_type(&new_type);
-
-                    
-
-                    // + for concatenation
-                    // sets use - for difference, just model it as a concatenation for now
-                    if (op == "+" || op == "-") 
-                        && vm.is_subtype(&new_type, "collection") {
-                        // todo, dirty workaround for augmented assign
-                        new_ptr = *left_address;
-
-                        let new_col;
-                        {
-                            let left_obj = vm.get_object(left_address);
-                            let right_obj = vm.get_object(right_address);
-                            let left_col = left_obj.get_elements();
-                            let right_col = right_obj.get_elements();
-                            new_col = left_col.concatenate(right_col);
-                        }
-
-                        let mut new_object = vm.get_object_mut(&new_ptr);
-                        new_object.set_elements(new_col);
-                    }   
-
-                    result.add_mapping(new_path, new_ptr);              
-                } else if op == "*" && b1 && b3 {
-                    let old_type;
-                    let collection;
-                    {
-                        let old_object = vm.get_object(left_address);
-                        old_type = *old_object.get_extension().first().unwrap();
-                        collection = old_object.get_elements().clone();
-                    }
-
-                    let new_ptr = vm.object_of_type_pointer(&old_type);
-                    result.add_mapping(new_path, new_ptr);
-                    let mut new_object = vm.get_object_mut(&new_ptr);
-                    new_object.set_elements(collection); 
-                } else if op == "*" && b2 && b4 {
-                    let old_type;
-                    let collection;
-                    {
-                        let old_object = vm.get_object(right_address);
-                        old_type = *old_object.get_extension().first().unwrap();
-                        collection = old_object.get_elements().clone();
-                    }
-
-                    let new_ptr = vm.object_of_type_pointer(&old_type);
-                    result.add_mapping(new_path, new_ptr);
-                    let mut new_object = vm.get_object_mut(&new_ptr);
-                    new_object.set_elements(collection); 
-                } else {
-                    let kb = vm.knowledge();
-                    let left_object = vm.get_object(left_address);
-                    let left_type = left_object.get_type_name(kb);
-                    let right_object = vm.get_object(right_address);
-                    let right_type = right_object.get_type_name(kb);
-
-                    match error.entry((left_type, right_type)) {
-                        Entry::Vacant(o) => {
-                            let mut left_set = BTreeSet::new();
-                            let mut right_set = BTreeSet::new();
-                            left_set.insert(left_path.clone());
-                            right_set.insert(right_path.clone());
-                            o.insert((left_set, right_set));
-                        }
-                        Entry::Occupied(mut entry) => {
-                            let &mut (ref mut left_set, ref mut right_set) = entry.get_mut();
-                            left_set.insert(left_path.clone());
-                            right_set.insert(right_path.clone());
-                        }
-                    }
+                if let Some(mut changes) = update_in_place(vm, op, left_address, right_address, &change_path) {
+                    total_changes.append(&mut changes);
+                    result.add_mapping(new_path, *left_address);
+                    continue;
+                }
+            }
+
+            if let Some((new_type, reflected)) = operation {
+                let new_ptr = make_result(vm, &new_type, op, left_address, right_address, reflected);
+                result.add_mapping(new_path, new_ptr);
+                continue;
+            }
+
+            let kb = vm.knowledge();
+            let left_object = vm.get_object(left_address);
+            let left_type = left_object.get_type_name(kb);
+            let right_object = vm.get_object(right_address);
+            let right_type = right_object.get_type_name(kb);
+
+            match error.entry((left_type, right_type)) {
+                Entry::Vacant(o) => {
+                    let mut left_set = BTreeSet::new();
+                    let mut right_set = BTreeSet::new();
+                    left_set.insert(left_path.clone());
+                    right_set.insert(right_path.clone());
+                    o.insert((left_set, right_set));
+                }
+                Entry::Occupied(mut entry) => {
+                    let &mut (ref mut left_set, ref mut right_set) = entry.get_mut();
+                    left_set.insert(left_path.clone());
+                    right_set.insert(right_path.clone());
                 }
             }
         }
+    }
+
+    if !error.is_empty() {
+        let content = BinOpInvalid::new(op.to_owned(), error);
+        let message = Message::Output { 
+            source: vm.current_node().clone(),
+            content: Box::new(content)};
+        CHANNEL.publish(message);
+    }
+
+    for ((format, problem), paths) in format_errors {
+        let content = FormatInvalid::new(format, problem, paths);
+        let message = Message::Output {
+            source: vm.current_node().clone(),
+            content: Box::new(content),
+        };
+        CHANNEL.publish(message);
+    }
 
-        if !error.is_empty() {
-            let content = BinOpInvalid::new(op.to_owned(), error);
-            let message = Message::Output { 
-                source: vm.current_node().clone(),
-                content: Box::new(content)};
-            CHANNEL.publish(message);
+    ExecutionResult {
+        flow: FlowControl::Continue,
+        dependencies: vec!(),
+        changes: total_changes,
+        result: result,
+    }
+}
+
+// the most specific rule of the left side wins, the right side's reflected
+// rules are only used when the left side doesn't know the operation
+fn operation_result(vm: &VirtualMachine, left: &Pointer, op: &str, right: &Pointer) -> Option<(String, bool)> {
+    let kb = vm.knowledge();
+    let left_types: Vec<&String> = vm.ancestors(left).iter().map(|t| kb.get_type_name(t)).collect();
+    let right_types: Vec<&String> = vm.ancestors(right).iter().map(|t| kb.get_type_name(t)).collect();
+
+    for left_type in &left_types {
+        for right_type in &right_types {
+            if let Some(result) = kb.operation_result(left_type, op, right_type) {
+                return Some((result.clone(), false));
+            }
         }
+    }
 
-        ExecutionResult {
-            flow: FlowControl::Continue,
-            dependencies: total_dependencies,
-            changes: total_changes,
-            result: result,
+    for right_type in &right_types {
+        for left_type in &left_types {
+            if let Some(result) = kb.reflected_operation_result(right_type, op, left_type) {
+                return Some((result.clone(), true));
+            }
+        }
+    }
+
+    None
+}
+
+fn make_result(vm: &mut VirtualMachine, 
+               new_type: &str, 
+               op: &str, 
+               left: &Pointer, 
+               right: &Pointer, 
+               reflected: bool) 
+               -> Pointer {
+    if !vm.is_subtype(new_type, "collection") {
+        return vm.object_of_type(&new_type.to_owned());
+    }
+
+    let left_collection = vm.is_instance(left, &"collection".to_owned());
+    let right_collection = vm.is_instance(right, &"collection".to_owned());
+
+    // merging dicts, any key of either side could be in the result
+    if new_type == "dict" {
+        let new_ptr = new_dict(vm);
+        let new_mapping = Mapping::simple(Path::empty(), new_ptr);
+        update_dict(vm, &new_mapping, &Mapping::simple(Path::empty(), *left));
+        update_dict(vm, &new_mapping, &Mapping::simple(Path::empty(), *right));
+        return new_ptr;
+    }
+
+    // intersections and differences keep some of the elements of the left side
+    // symmetric differences some of either side
+    if (op == "&" || op == "-" || op == "^") && left_collection && right_collection {
+        let mut chunks = vec!(optional_elements(vm, left));
+
+        if op == "^" {
+            chunks.push(optional_elements(vm, right));
+        }
+
+        let new_ptr = vm.object_of_type(new_type);
+        vm.get_object_mut(&new_ptr).define_elements(chunks, Path::empty());
+        return new_ptr;
+    }
+
+    // + for concatenation, | for unions
+    if (op == "+" || op == "|") && left_collection && right_collection {
+        let new_col = {
+            let left_col = vm.get_object(left).get_elements();
+            let right_col = vm.get_object(right).get_elements();
+            left_col.concatenate(right_col)
+        };
+
+        let new_ptr = vm.object_of_type(new_type);
+        vm.get_object_mut(&new_ptr).set_elements(new_col);
+        return new_ptr;
+    }
+
+    // repeating a sequence keeps its elements
+    let sequence = if reflected { right } else { left };
+    let collection = vm.get_object(sequence).get_elements().clone();
+
+    let new_ptr = vm.object_of_type(&new_type.to_owned());
+    let mut new_object = vm.get_object_mut(&new_ptr);
+    new_object.set_elements(collection);
+    new_ptr
+}
+
+// lists, sets and dicts don't make a new object for augmented assignments
+// none when the left side gets replaced instead
+fn update_in_place(vm: &mut VirtualMachine,
+                   op: &str,
+                   left: &Pointer,
+                   right: &Pointer,
+                   path: &Path)
+                   -> Option<Vec<AnalysisItem>> {
+    let right_mapping = Mapping::simple(Path::empty(), *right);
+    let is_list = vm.is_instance(left, "list");
+    let is_set = vm.is_instance(left, "set");
+    let is_dict = vm.is_instance(left, "dict");
+
+    let mut changes = Vec::new();
+
+    if is_dict && op == "|" {
+        changes = update_dict(vm, &Mapping::simple(Path::empty(), *left), &right_mapping);
+    } else if (is_list && op == "+") || (is_set && (op == "|" || op == "^")) {
+        // extending with an empty collection changes nothing
+        if let Some(chunk) = collection_chunk(vm, &right_mapping) {
+            let current_path = vm.current_path().clone();
+            vm.get_object_mut(left).append_element(chunk, current_path);
         }
+    } else if (is_list && op == "*") || (is_set && (op == "-" || op == "&")) {
+        // repetition and removing elements keep the same kinds of elements
+    } else {
+        return None;
     }
+
+    vm.store_object_change(*left, path);
+    changes.push(AnalysisItem::Object(*left));
+
+    Some(changes)
+}
+
+// the values for a constant %-format string have to match its placeholders
+// a tuple holds the values, anything else is a single value
+fn format_problems(vm: &VirtualMachine, left: &Pointer, right: &Pointer) -> Vec<(String, String, Path)> {
+    let mut problems = Vec::new();
+
+    let (format, placeholders) = match vm.string_value(left) {
+        Some(format) => match placeholder_count(format) {
+            Some(placeholders) => (format!("{:?}", format), placeholders),
+            None => return problems,
+        },
+        None => return problems,
+    };
+
+    // a mapping can provide any amount of values
+    if vm.is_instance(right, "dict") {
+        return problems;
+    }
+
+    if vm.is_instance(right, "tuple") {
+        for (path, min, max) in vm.get_object(right).size_range() {
+            if let (Some(min), Some(max)) = (min, max) {
+                if min == max && min != placeholders {
+                    let problem = format!("{} values expected, {} given", placeholders, min);
+                    problems.push((format.clone(), problem, path));
+                }
+            }
+        }
+    } else if placeholders != 1 {
+        let problem = format!("{} values expected, 1 given", placeholders);
+        problems.push((format, problem, Path::empty()));
+    }
+
+    problems
 }
@@ -6,6 +6,9 @@ impl BreakExecutor for PythonBreak {
     fn execute(&self, env: Environment) -> ExecutionResult {
         let Environment { vm, .. } = env;
 
+        let path = vm.current_path().clone();
+        vm.add_break(path);
+
         let result_mapping = Mapping::simple(Path::empty(), vm.knowledge().constant("None"));
 
         ExecutionResult {
@@ -15,4 +18,89 @@ impl BreakExecutor for PythonBreak {
             result: result_mapping,
         }
     }
-}
\ No newline at end of file
+}
+
+// the else clause of a loop only runs on the paths that didn't break
+pub fn loop_else(vm: &mut VirtualMachine, 
+                 executors: &Executors, 
+                 or_else: &GastNode, 
+                 breaks: Vec<Path>) 
+                 -> ExecutionResult {
+    if let NodeType::Block { ref content } = or_else.kind {
+        if content.is_empty() {
+            return ExecutionResult {
+                flow: FlowControl::Continue,
+                dependencies: vec!(),
+                changes: vec!(),
+                result: Mapping::new(),
+            };
+        }
+    }
+
+    if breaks.is_empty() {
+        return vm.execute(executors, or_else);
+    }
+
+    let original_restriction = vm.get_branch_restrictions().clone();
+
+    let mut positive = vm.current_path().clone();
+    positive.add_node(PathNode::Condition(vm.current_node().clone(), 0, 2));
+    let mut negative = vm.current_path().clone();
+    negative.add_node(PathNode::Condition(vm.current_node().clone(), 1, 2));
+
+    vm.push_path(positive);
+    vm.add_branch_restrictions(breaks.clone());
+    let else_result = vm.execute(executors, or_else);
+    vm.pop_path();
+    vm.set_branch_restrictions(original_restriction);
+
+    let changes = else_result.changes;
+    let dependencies = else_result.dependencies;
+
+    vm.next_branch(&changes);
+
+    // the loop broke off, nothing happens here
+    vm.push_path(negative);
+    vm.pop_path();
+
+    let hide_as_loop = match else_result.flow {
+        FlowControl::Continue => None,
+        FlowControl::TerminateCall => Some(false),
+        FlowControl::TerminateLoop => Some(true),
+    };
+
+    let no_break = no_break(&breaks);
+
+    vm.merge_branches(&changes, vec!(hide_as_loop, None), vec!(breaks, no_break));
+
+    ExecutionResult {
+        flow: FlowControl::Continue,
+        dependencies: dependencies,
+        changes: changes,
+        result: Mapping::new(),
+    }
+}
+
+// the paths that didn't take any of the breaks
+// they diverge from every break path at some condition
+fn no_break(breaks: &[Path]) -> Vec<Path> {
+    let mut result = vec!(Path::empty());
+
+    for path in breaks {
+        let mut next = Vec::new();
+
+        for current in &result {
+            for other in path.reverse() {
+                if current.mergeable(&other) {
+                    let mut new_path = current.clone();
+                    new_path.merge_into(other);
+                    next.push(new_path);
+                }
+            }
+        }
+
+        result = next;
+    }
+
+    result
+}
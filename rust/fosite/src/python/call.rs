@@ -31,6 +31,17 @@ impl CallExecutor for PythonCall {
         vm.add_node(node);
 
         for arg in arg_nodes.iter() {
+            // unpacked arguments could fill any number of positions
+            if let NodeType::UnOp { ref op, ref value } = arg.kind {
+                if op == "*" {
+                    let mut arg_result = vm.execute(executors, value);
+                    total_changes.append(&mut arg_result.changes);
+                    total_dependencies.append(&mut arg_result.dependencies);
+                    kwargs.push( ("*".to_owned(), arg_result.result) );
+                    continue;
+                }
+            }
+
             let mut arg_result = vm.execute(executors, arg);
             total_changes.append(&mut arg_result.changes);
             total_dependencies.append(&mut arg_result.dependencies);
@@ -105,13 +116,13 @@ impl CallExecutor for PythonCall {
             // todo filter the body changes
             if let Some(body_result) = vm.call(executors, &address, aug_args, aug_kwargs) {
                 for change in body_result.changes {
-                    if let AnalysisItem::Object(_) = change {
+                    if change.escapes_call() {
                         body_changes.push(change);
                     }
                 }
                 
                 for dependency in body_result.dependencies {
-                    if let AnalysisItem::Object(_) = dependency {
+                    if dependency.escapes_call() {
                         body_changes.push(dependency);
                     }
                 }
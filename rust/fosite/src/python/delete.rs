@@ -0,0 +1,290 @@
+use core::*;
+
+use super::modules::constant_key;
+use super::modules::remove_key;
+use super::PythonAttribute;
+use super::PythonIndex;
+use super::PythonSlice;
+
+pub struct PythonDelete { }
+
+impl DeleteExecutor for PythonDelete {
+    fn execute(&self, env: Environment, targets: &[GastNode]) -> ExecutionResult {
+        let Environment { vm, executors } = env;
+
+        let mut total_changes = Vec::new();
+        let mut total_dependencies = Vec::new();
+
+        for target in targets {
+            let mut result = self.delete(vm, executors, target);
+            total_changes.append(&mut result.changes);
+            total_dependencies.append(&mut result.dependencies);
+        }
+
+        let result_mapping = Mapping::simple(Path::empty(), vm.knowledge().constant("None"));
+
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: total_dependencies,
+            changes: total_changes,
+            result: result_mapping,
+        }
+    }
+}
+
+impl PythonDelete {
+    fn delete(&self,
+              vm: &mut VirtualMachine,
+              executors: &Executors,
+              target: &GastNode)
+              -> ExecutionResult {
+        match target.kind {
+            NodeType::Sequence { ref content } |
+            NodeType::List { ref content } => {
+                let mut changes = Vec::new();
+                let mut dependencies = Vec::new();
+
+                for target in content {
+                    let mut result = self.delete(vm, executors, target);
+                    changes.append(&mut result.changes);
+                    dependencies.append(&mut result.dependencies);
+                }
+
+                ExecutionResult {
+                    flow: FlowControl::Continue,
+                    dependencies,
+                    changes,
+                    result: Mapping::new(),
+                }
+            }
+            NodeType::Identifier { ref name } => {
+                // deleting something that isn't there fails the same way reading it does
+                let mut result = vm.execute(executors, target);
+                let mut deletion = self.delete_identifier(vm, name);
+
+                result.changes.append(&mut deletion.changes);
+                result.dependencies.append(&mut deletion.dependencies);
+                result.result = Mapping::new();
+                result
+            }
+            NodeType::Attribute { ref parent, ref attribute } => {
+                let mut result = self.read_target(vm, executors, target, parent, |vm, mapping| {
+                    PythonAttribute {}.attribute_mapping(vm, executors, parent, mapping, attribute)
+                });
+
+                let mut deletion = self.delete_attribute(vm, parent, attribute, &result.result);
+
+                result.changes.append(&mut deletion.changes);
+                result.dependencies.append(&mut deletion.dependencies);
+                result.result = Mapping::new();
+                result
+            }
+            NodeType::Index { target: ref inner, ref index } => {
+                let mut result = self.read_target(vm, executors, target, inner, |vm, mapping| {
+                    PythonIndex {}.index_mapping(vm, executors, inner, mapping, index)
+                });
+
+                let mut deletion = self.delete_index(vm, inner, index, &result.result);
+
+                result.changes.append(&mut deletion.changes);
+                result.dependencies.append(&mut deletion.dependencies);
+                result.result = Mapping::new();
+                result
+            }
+            NodeType::Slice { target: ref inner, ref lower, ref upper, ref step } => {
+                let mut result = self.read_target(vm, executors, target, inner, |vm, mapping| {
+                    ExecutionResult {
+                        flow: FlowControl::Continue,
+                        dependencies: vec![],
+                        changes: vec![],
+                        result: PythonSlice {}.slice_mapping(vm, inner, mapping, lower, upper, step),
+                    }
+                });
+
+                let mut deletion = self.delete_slice(vm, &result.result);
+
+                result.changes.append(&mut deletion.changes);
+                result.dependencies.append(&mut deletion.dependencies);
+                result.result = Mapping::new();
+                result
+            }
+            // nothing to forget for other targets
+            _ => vm.execute(executors, target),
+        }
+    }
+
+    // evaluate the parent of a target once, then read the target from it
+    // deleting something that isn't there fails the same way reading it does
+    // the result holds the parent
+    fn read_target<F>(&self,
+                      vm: &mut VirtualMachine,
+                      executors: &Executors,
+                      target: &GastNode,
+                      parent: &GastNode,
+                      read: F)
+                      -> ExecutionResult
+        where F: FnOnce(&mut VirtualMachine, &Mapping) -> ExecutionResult {
+        let mut node = vm.current_node().clone();
+        node.push(target.id);
+        vm.add_node(node);
+
+        let mut parent_result = vm.execute(executors, parent);
+        let mut read_result = read(vm, &parent_result.result);
+
+        vm.pop_node();
+
+        parent_result.dependencies.append(&mut read_result.dependencies);
+        parent_result.changes.append(&mut read_result.changes);
+        parent_result
+    }
+
+    fn delete_identifier(&self, vm: &mut VirtualMachine, name: &str) -> ExecutionResult {
+        let item = vm.identifier_item(name);
+
+        let mut mapping = OptionalMapping::new();
+        mapping.add_mapping(Path::empty(), None);
+        let mapping = mapping.augment(PathNode::Deletion(vm.current_node().clone(), name.to_owned()));
+
+        let path = vm.current_path().clone();
+
+        vm.store_identifier_change(item.clone(), &path, &Mapping::new());
+
+        {
+            let scope = vm.declaring_scope_mut(name);
+            scope.set_optional_mapping(name.to_owned(), path, mapping);
+        }
+
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: vec![],
+            changes: vec![item],
+            result: Mapping::new(),
+        }
+    }
+
+    fn delete_attribute(&self,
+                        vm: &mut VirtualMachine,
+                        parent: &GastNode,
+                        attribute: &str,
+                        parent_mapping: &Mapping)
+                        -> ExecutionResult {
+        let mut mapping = OptionalMapping::new();
+        mapping.add_mapping(Path::empty(), None);
+        let mapping = mapping.augment(PathNode::Deletion(vm.current_node().clone(),
+                                                         format!("{}.{}",
+                                                                 parent.to_string(),
+                                                                 attribute)));
+
+        let mut changes = Vec::new();
+
+        for &(ref parent_path, ref parent_address) in parent_mapping {
+            let mut new_path = vm.current_path().clone();
+            new_path.merge_into(parent_path.clone());
+
+            changes.push(AnalysisItem::Object(*parent_address));
+
+            vm.store_object_change(*parent_address, &new_path);
+
+            let parent_object = vm.get_object_mut(parent_address);
+            parent_object.assign_opt_attribute(attribute.to_owned(), new_path, mapping.clone());
+        }
+
+        if let Some(item) = parent.kind.to_analysis_item() {
+            let attribute_item = AnalysisItem::Attribute(Box::new(item), attribute.to_owned());
+            changes.push(attribute_item.clone());
+
+            let path = vm.current_path().clone();
+            vm.store_identifier_change(attribute_item, &path, parent_mapping);
+        }
+
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: vec![],
+            changes,
+            result: Mapping::new(),
+        }
+    }
+
+    fn delete_index(&self,
+                    vm: &mut VirtualMachine,
+                    target: &GastNode,
+                    index: &GastNode,
+                    target_mapping: &Mapping)
+                    -> ExecutionResult {
+        let mut changes = Vec::new();
+
+        let seq_type = *vm.knowledge().get_type("mutable_sequence").unwrap();
+        let dict_type = *vm.knowledge().get_type("dict").unwrap();
+
+        // only the first and last element can be removed exactly
+        let position = match index.kind {
+            NodeType::Int { value: 0 } => Some(0),
+            NodeType::Int { value: -1 } => Some(-1),
+            _ => None,
+        };
+
+        for &(ref target_path, ref target_address) in target_mapping {
+            let mut new_path = vm.current_path().clone();
+            new_path.merge_into(target_path.clone());
+
+            let types = vm.ancestors(target_address);
+
+            if types.contains(&seq_type) {
+                changes.push(AnalysisItem::Object(*target_address));
+                vm.store_object_change(*target_address, &new_path);
+
+                let current_path = vm.current_path().clone();
+                vm.get_object_mut(target_address).remove_element(position, current_path);
+            } else if types.contains(&dict_type) {
+                // unknown keys can't be forgotten
+                if let Some(key) = constant_key(index) {
+                    changes.push(AnalysisItem::Object(*target_address));
+                    vm.store_object_change(*target_address, &new_path);
+
+                    let current_path = vm.current_path().clone();
+                    let cause = PathNode::Deletion(vm.current_node().clone(),
+                                                   format!("{}[{}]",
+                                                           target.to_string(),
+                                                           index.to_string()));
+                    remove_key(vm, target_address, &key, current_path, cause);
+                }
+            }
+        }
+
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: vec![],
+            changes,
+            result: Mapping::new(),
+        }
+    }
+
+    // which elements a slice covers isn't known, any number of them could be gone
+    fn delete_slice(&self, vm: &mut VirtualMachine, target_mapping: &Mapping) -> ExecutionResult {
+        let mut changes = Vec::new();
+
+        let seq_type = *vm.knowledge().get_type("mutable_sequence").unwrap();
+
+        for &(ref target_path, ref target_address) in target_mapping {
+            if !vm.ancestors(target_address).contains(&seq_type) {
+                continue;
+            }
+
+            let mut new_path = vm.current_path().clone();
+            new_path.merge_into(target_path.clone());
+
+            changes.push(AnalysisItem::Object(*target_address));
+            vm.store_object_change(*target_address, &new_path);
+
+            let current_path = vm.current_path().clone();
+            vm.get_object_mut(target_address).remove_all_elements(current_path);
+        }
+
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: vec![],
+            changes,
+            result: Mapping::new(),
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use core::*;
 
+use super::loop_else;
+
 use std::collections::BTreeSet;
 
 pub struct PythonFor { }
@@ -8,7 +10,8 @@ impl ForEachExecutor for PythonFor {
     fn execute(&self,
                env: Environment,
                before: &GastNode,
-               body: &GastNode)
+               body: &GastNode,
+               or_else: &GastNode)
                -> ExecutionResult {
         let Environment { vm, executors } = env;
 
@@ -22,15 +25,22 @@ impl ForEachExecutor for PythonFor {
         total_changes.append(&mut gen_result.changes);
         total_dependencies.append(&mut gen_result.dependencies);
 
+        vm.new_breaks();
         let mut result = self.branch(vm, executors, body, &gen_result.result);
+        let breaks = vm.get_breaks();
 
         total_changes.append(&mut result.changes);
         total_dependencies.append(&mut result.dependencies);
 
+        let mut else_result = loop_else(vm, executors, or_else, breaks);
+
+        total_changes.append(&mut else_result.changes);
+        total_dependencies.append(&mut else_result.dependencies);
+
         ExecutionResult {
             changes: total_changes,
             dependencies: total_dependencies,
-            flow: FlowControl::Continue,
+            flow: else_result.flow,
             result: Mapping::new(),
         }
     }
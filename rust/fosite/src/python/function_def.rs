@@ -2,12 +2,21 @@ use core::*;
 use std::sync::Mutex;
 use std::collections::BTreeSet;
 
+use super::modules::is_unpacked;
+use super::modules::has_unpacked;
+use super::modules::unpacked_elements;
+
+// parameters without a default value have None
+type Parameters = Vec<(String, Option<Mapping>)>;
+
 lazy_static! {
-    static ref ARGS: Mutex<Vec<Vec<(String, Mapping)>>> = Mutex::new(Vec::new());
-    static ref KW_ARGS: Mutex<Vec<Vec<(String, Mapping)>>> = Mutex::new(Vec::new());
+    static ref ARGS: Mutex<Vec<Parameters>> = Mutex::new(Vec::new());
+    static ref KW_ARGS: Mutex<Vec<Parameters>> = Mutex::new(Vec::new());
     static ref VARARG: Mutex<Vec<Option<String>>> = Mutex::new(Vec::new());
     static ref KW_VARARG: Mutex<Vec<Option<String>>> = Mutex::new(Vec::new());
     static ref BODY: Mutex<Vec<GastNode>> = Mutex::new(Vec::new());
+    static ref SIGNATURE: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    static ref LOCALS: Mutex<Vec<BTreeSet<String>>> = Mutex::new(Vec::new());
 }
 
 pub struct PythonFunction {
@@ -34,22 +43,36 @@ impl FunctionDefExecutor for PythonFunction {
 
         for node in rpos.iter() {
             if let NodeType::Argument {ref name, ref value} = node.kind {
-                let mut eval_result = vm.execute(executors, value);
-                rpos_evaluated.push((name.clone(), eval_result.result));
-                dependencies.append(&mut eval_result.dependencies);
-                changes.append(&mut eval_result.changes);
+                let default = if has_default(value) {
+                    let mut eval_result = vm.execute(executors, value);
+                    dependencies.append(&mut eval_result.dependencies);
+                    changes.append(&mut eval_result.changes);
+                    Some(eval_result.result)
+                } else {
+                    None
+                };
+
+                rpos_evaluated.push((name.clone(), default));
             }
         }
 
         for node in rkw.iter() {
             if let NodeType::Argument {ref name, ref value} = node.kind {
-                let mut eval_result = vm.execute(executors, value);
-                rkw_evaluated.push((name.clone(), eval_result.result));
-                dependencies.append(&mut eval_result.dependencies);
-                changes.append(&mut eval_result.changes);
+                let default = if has_default(value) {
+                    let mut eval_result = vm.execute(executors, value);
+                    dependencies.append(&mut eval_result.dependencies);
+                    changes.append(&mut eval_result.changes);
+                    Some(eval_result.result)
+                } else {
+                    None
+                };
+
+                rkw_evaluated.push((name.clone(), default));
             }
         }
 
+        let signature = signature(name, &rpos_evaluated, &rkw_evaluated, vararg, kw_vararg);
+
         let index = ARGS.lock().unwrap().len();
 
         ARGS.lock().unwrap().push(rpos_evaluated);
@@ -57,7 +80,8 @@ impl FunctionDefExecutor for PythonFunction {
         VARARG.lock().unwrap().push(vararg.clone());
         KW_VARARG.lock().unwrap().push(kw_vararg.clone());
         BODY.lock().unwrap().push(body.clone());
-        
+        SIGNATURE.lock().unwrap().push(signature);
+        LOCALS.lock().unwrap().push(local_names(body));
 
         let inner = move | env: Environment, args: Vec<Mapping>, kw_args: Vec<(String, Mapping)> | {
             let Environment { vm, executors } = env;
@@ -68,18 +92,29 @@ impl FunctionDefExecutor for PythonFunction {
             let mut aug_kwargs = Vec::new();
 
             for &(ref n, ref a) in &ARGS.lock().unwrap()[index] {
-                aug_args.push( (n.clone(), a.clone().augment(new_node.clone())) );
+                aug_args.push( (n.clone(), a.clone().map(|a| a.augment(new_node.clone()))) );
             }
 
             for &(ref n, ref a) in &KW_ARGS.lock().unwrap()[index] {
-                aug_kwargs.push( (n.clone(), a.clone().augment(new_node.clone())) );
+                aug_kwargs.push( (n.clone(), a.clone().map(|a| a.augment(new_node.clone()))) );
+            }
+
+            for name in LOCALS.lock().unwrap()[index].iter() {
+                vm.last_scope_mut().declare_local(name.clone());
             }
 
-            assign_positional(vm, executors,
+            let signature = SIGNATURE.lock().unwrap()[index].clone();
+
+            let assign_result = assign_arguments(vm, executors, &signature,
                         &aug_args, &aug_kwargs, 
                         &args, &kw_args,
                         &VARARG.lock().unwrap()[index], &KW_VARARG.lock().unwrap()[index]);
 
+            // python doesn't execute the body of an invalid call
+            if let FlowControl::TerminateCall = assign_result.flow {
+                return assign_result;
+            }
+
             let body = &BODY.lock().unwrap()[index].clone();
             let body_result = vm.execute(executors, body);
             
@@ -107,10 +142,123 @@ impl FunctionDefExecutor for PythonFunction {
     }
 }
 
-fn assign_positional(vm: &mut VirtualMachine,
+// required arguments have an empty block instead of a default value
+fn has_default(value: &GastNode) -> bool {
+    match value.kind {
+        NodeType::Block { ref content } => !content.is_empty(),
+        _ => true,
+    }
+}
+
+// python decides which names are local before running the function
+fn local_names(body: &GastNode) -> BTreeSet<String> {
+    let mut assigned = BTreeSet::new();
+    let mut declared = BTreeSet::new();
+    assigned_names(body, &mut assigned, &mut declared);
+
+    assigned.difference(&declared).cloned().collect()
+}
+
+// nested functions and comprehensions have their own scope
+fn assigned_names(node: &GastNode, assigned: &mut BTreeSet<String>, declared: &mut BTreeSet<String>) {
+    match node.kind {
+        NodeType::Block { ref content } => {
+            for statement in content {
+                assigned_names(statement, assigned, declared);
+            }
+        }
+        NodeType::Assignment { ref targets, .. } |
+        NodeType::Delete { ref targets } => {
+            for target in targets {
+                target_names(target, assigned);
+            }
+        }
+        NodeType::If { ref body, ref or_else, .. } |
+        NodeType::While { ref body, ref or_else, .. } => {
+            assigned_names(body, assigned, declared);
+            assigned_names(or_else, assigned, declared);
+        }
+        NodeType::ForEach { ref before, ref body, ref or_else } => {
+            if let NodeType::Generator { ref target, .. } = before.kind {
+                target_names(target, assigned);
+            }
+
+            assigned_names(body, assigned, declared);
+            assigned_names(or_else, assigned, declared);
+        }
+        NodeType::FunctionDef { ref name, .. } => {
+            assigned.insert(name.clone());
+        }
+        NodeType::Import { ref parts, ref into, .. } => {
+            match *into {
+                Some(ref into) => {
+                    assigned.insert(into.clone());
+                }
+                None => assigned.extend(parts.iter().map(|&(_, ref alias)| alias.clone())),
+            }
+        }
+        NodeType::Global { ref names } |
+        NodeType::Nonlocal { ref names } => {
+            declared.extend(names.iter().cloned());
+        }
+        _ => (),
+    }
+}
+
+fn target_names(target: &GastNode, assigned: &mut BTreeSet<String>) {
+    match target.kind {
+        NodeType::Identifier { ref name } => {
+            assigned.insert(name.clone());
+        }
+        NodeType::Sequence { ref content } |
+        NodeType::List { ref content } => {
+            for target in content {
+                target_names(target, assigned);
+            }
+        }
+        // starred targets
+        NodeType::UnOp { ref value, .. } => target_names(value, assigned),
+        _ => (),
+    }
+}
+
+// the signature as it was written, without the default values
+fn signature(name: &str,
+             arg: &[(String, Option<Mapping>)],
+             kwonly: &[(String, Option<Mapping>)],
+             vararg: &Option<String>,
+             kw_vararg: &Option<String>) 
+             -> String {
+    let parameter = |&(ref name, ref default): &(String, Option<Mapping>)| {
+        match *default {
+            Some(_) => format!("{}=...", name),
+            None => name.clone(),
+        }
+    };
+
+    let mut parameters: Vec<String> = arg.iter().map(&parameter).collect();
+
+    if let Some(ref name) = *vararg {
+        parameters.push(format!("*{}", name));
+    } else if !kwonly.is_empty() {
+        parameters.push("*".to_owned());
+    }
+
+    parameters.extend(kwonly.iter().map(&parameter));
+
+    if let Some(ref name) = *kw_vararg {
+        parameters.push(format!("**{}", name));
+    }
+
+    format!("{}({})", name, parameters.join(", "))
+}
+
+// bind the given arguments to the parameters, like python does
+fn assign_arguments(vm: &mut VirtualMachine,
                         executors: &Executors,
-                        arg: &[(String, Mapping)],
-                        kwonly: &[(String, Mapping)],
+                        signature: &str,
+                        arg: &[(String, Option<Mapping>)],
+                        kwonly: &[(String, Option<Mapping>)],
                         gpos: &[Mapping],
                         gkw: &[(String, Mapping)],
                         vararg: &Option<String>,
@@ -120,24 +268,98 @@ fn assign_positional(vm: &mut VirtualMachine,
     let mut dependencies = vec!();
     let mut changes = vec!();
 
-    // todo, use the default value before moving to varargs
-    if !arg.is_empty() && !gpos.is_empty() {
-        let &(ref name, _) = &arg[0];
-        let mapping = gpos[0].clone();
-        let mut aresult = vm.assign_direct(executors, name.clone(), mapping);
+    let mut problems = Vec::new();
+    let mut bound = BTreeSet::new();
+
+    // the arity can't be checked when arguments are unpacked
+    let unpacked = has_unpacked(gkw);
+
+    for (&(ref name, _), mapping) in arg.iter().zip(gpos) {
+        let mut aresult = vm.assign_direct(executors, name.clone(), mapping.clone());
+        dependencies.append(&mut aresult.dependencies);
+        changes.append(&mut aresult.changes);
+        bound.insert(name.clone());
+    }
+
+    let extra = if gpos.len() > arg.len() { &gpos[arg.len()..] } else { &[] };
+
+    if vararg.is_some() {
+        let mut aresult = assign_vararg(vm, executors, extra, vararg);
+        dependencies.append(&mut aresult.dependencies);
+        changes.append(&mut aresult.changes);
+    } else if !extra.is_empty() && !unpacked {
+        problems.push(format!("{} positional arguments are given, but it takes at most {}", 
+            gpos.len(), arg.len()));
+    }
+
+    let mut unknown = Vec::new();
+
+    for &(ref name, ref mapping) in gkw {
+        if is_unpacked(name) {
+            continue;
+        }
+
+        if bound.contains(name) {
+            problems.push(format!("argument {} is given both positionally and by keyword", name));
+        } else if arg.iter().chain(kwonly).any(|&(ref param, _)| param == name) {
+            let mut aresult = vm.assign_direct(executors, name.clone(), mapping.clone());
+            dependencies.append(&mut aresult.dependencies);
+            changes.append(&mut aresult.changes);
+            bound.insert(name.clone());
+        } else {
+            unknown.push((name.clone(), mapping.clone()));
+        }
+    }
+
+    if kw_vararg.is_some() {
+        let mut aresult = assign_kw_vararg(vm, executors, &unknown, kw_vararg);
         dependencies.append(&mut aresult.dependencies);
         changes.append(&mut aresult.changes);
-        let mut intermediate = assign_positional(vm, executors, &arg[1..], kwonly, &gpos[1..], gkw, vararg, kw_vararg);
-        dependencies.append(&mut intermediate.dependencies);
-        changes.append(&mut intermediate.changes);
     } else {
-        let mut intermediate = assign_vararg(vm, executors, arg, kwonly, gpos, gkw, vararg, kw_vararg);
-        dependencies.append(&mut intermediate.dependencies);
-        changes.append(&mut intermediate.changes);
+        for &(ref name, _) in &unknown {
+            problems.push(format!("there is no argument called {}", name));
+        }
+    }
+
+    // the remaining parameters could get any of the unpacked values
+    let elements = unpacked_elements(vm, gkw);
+
+    for &(ref name, ref default) in arg.iter().chain(kwonly) {
+        if bound.contains(name) {
+            continue;
+        }
+
+        match *default {
+            Some(ref mapping) => {
+                let mut aresult = vm.assign_direct(executors, name.clone(), mapping.clone());
+                dependencies.append(&mut aresult.dependencies);
+                changes.append(&mut aresult.changes);
+            }
+            None if unpacked => {
+                if !elements.is_empty() {
+                    let mut aresult = vm.assign_direct(executors, name.clone(), elements.clone());
+                    dependencies.append(&mut aresult.dependencies);
+                    changes.append(&mut aresult.changes);
+                }
+            }
+            None => problems.push(format!("argument {} is missing", name)),
+        }
+    }
+
+    let mut flow = FlowControl::Continue;
+
+    if !problems.is_empty() {
+        flow = FlowControl::TerminateCall;
+
+        let content = ArityInvalid::new(signature.to_owned(), problems, vm.current_path().clone());
+        let message = Message::Output { 
+            source: vm.current_node().clone(),
+            content: Box::new(content)};
+        CHANNEL.publish(message);
     }
 
     ExecutionResult {
-        flow: FlowControl::Continue,
+        flow: flow,
         dependencies: dependencies,
         changes: changes,
         result: Mapping::new(),
@@ -146,12 +368,8 @@ fn assign_positional(vm: &mut VirtualMachine,
 
 fn assign_vararg(vm: &mut VirtualMachine,
                         executors: &Executors,
-                        arg: &[(String, Mapping)],
-                        kwonly: &[(String, Mapping)],
                         gpos: &[Mapping],
-                        gkw: &[(String, Mapping)],
-                        vararg: &Option<String>,
-                        kw_vararg: &Option<String>) 
+                        vararg: &Option<String>) 
                         -> ExecutionResult {
 
     let mut dependencies = vec!();
@@ -185,57 +403,6 @@ fn assign_vararg(vm: &mut VirtualMachine,
         changes.append(&mut aresult.changes);
     } 
 
-    //todo assign rest of gpos
-    let mut intermediate = assign_kw(vm, executors, &[arg, kwonly].concat(), gkw, kw_vararg);
-    dependencies.append(&mut intermediate.dependencies);
-    changes.append(&mut intermediate.changes);
-
-
-    ExecutionResult {
-        flow: FlowControl::Continue,
-        dependencies: dependencies,
-        changes: changes,
-        result: Mapping::new(),
-    }
-}
-
-fn assign_kw(vm: &mut VirtualMachine,
-                        executors: &Executors,
-                        arg: &[(String, Mapping)],
-                        gkw: &[(String, Mapping)],
-                        kw_vararg: &Option<String>) 
-                        -> ExecutionResult {
-
-    let mut dependencies = vec!();
-    let mut changes = vec!();
-
-    let mut s1 = BTreeSet::new();
-    for &(ref name, _) in arg.iter() {
-        s1.insert(name);
-    }
-
-    if !gkw.is_empty() && !s1.is_empty() {
-        let mut next = Vec::new();
-
-        for &(ref name, ref mapping) in gkw.iter() {
-            if s1.contains(name) {
-                let mut aresult = vm.assign_direct(executors, name.clone(), mapping.clone());
-                dependencies.append(&mut aresult.dependencies);
-                changes.append(&mut aresult.changes);
-            } else {
-                next.push((name.clone(), mapping.clone()));
-            }
-        }
-
-        let mut intermediate = assign_kw_vararg(vm, executors, &next, gkw, kw_vararg);
-        dependencies.append(&mut intermediate.dependencies);
-        changes.append(&mut intermediate.changes);
-    } else {
-        let mut intermediate = assign_kw_vararg(vm, executors, arg, gkw, kw_vararg);
-        dependencies.append(&mut intermediate.dependencies);
-        changes.append(&mut intermediate.changes);
-    }
-
     ExecutionResult {
         flow: FlowControl::Continue,
         dependencies: dependencies,
@@ -246,7 +413,6 @@ fn assign_kw(vm: &mut VirtualMachine,
 
 fn assign_kw_vararg(vm: &mut VirtualMachine,
                         executors: &Executors,
-                        arg: &[(String, Mapping)],
                         gkw: &[(String, Mapping)],
                         kw_vararg: &Option<String>) 
                         -> ExecutionResult {
@@ -312,37 +478,10 @@ fn assign_kw_vararg(vm: &mut VirtualMachine,
         changes.append(&mut aresult.changes);
     }
 
-    let mut aresult = assign_defaults(vm, executors, arg); 
-    dependencies.append(&mut aresult.dependencies);
-    changes.append(&mut aresult.changes);
-
-    ExecutionResult {
-        flow: FlowControl::Continue,
-        dependencies: dependencies,
-        changes: changes,
-        result: Mapping::new(),
-    }
-}                 
-
-
-fn assign_defaults(vm: &mut VirtualMachine,
-                        executors: &Executors,
-                        arg: &[(String, Mapping)]) 
-                        -> ExecutionResult {
-
-    let mut dependencies = vec!();
-    let mut changes = vec!();
-
-    for &(ref name, ref mapping) in arg.iter() {
-        let mut aresult = vm.assign_direct(executors, name.clone(), mapping.clone());
-        dependencies.append(&mut aresult.dependencies);
-        changes.append(&mut aresult.changes);
-    }
-
     ExecutionResult {
         flow: FlowControl::Continue,
         dependencies: dependencies,
         changes: changes,
         result: Mapping::new(),
     }
-}
\ No newline at end of file
+}
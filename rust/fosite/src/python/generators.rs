@@ -1,5 +1,7 @@
 use core::*;
 
+use super::modules::dict_keys;
+
 pub struct PythonGenerator {
 
 }
@@ -10,10 +12,22 @@ impl GeneratorExecutor for PythonGenerator {
 
         let source_result = vm.execute(executors, source);
         let mut changes = source_result.changes;
-        let dependencies = source_result.dependencies;
+        let mut dependencies = source_result.dependencies;
 
-        let mut mapping = Mapping::new();
+        // dicts iterate over their keys
+        let mut iterables = Mapping::new();
         for (path, address) in source_result.result {
+            if vm.is_instance(&address, &"dict".to_owned()) {
+                for keys in dict_keys(vm, &address) {
+                    iterables.add_mapping(path.clone(), keys);
+                }
+            } else {
+                iterables.add_mapping(path, address);
+            }
+        }
+
+        let mut mapping = Mapping::new();
+        for (path, address) in iterables {
             let obj = vm.get_object(&address);
 
             // todo, replace current node with the node of the generator
@@ -27,19 +41,10 @@ impl GeneratorExecutor for PythonGenerator {
             }
         }
 
-        let mapping = mapping.clone()
-            .augment(PathNode::Assignment(vm.current_node().clone(), target.to_string()));
-
-        changes.push(AnalysisItem::Identifier(target.to_string()));
-
-        let path = vm.current_path().clone();
-
-        vm.store_identifier_change(AnalysisItem::Identifier(target.to_string()), &path, &mapping);
-
-        {
-            let mut scope = vm.last_scope_mut();
-            scope.set_mapping(target.to_string(), path, mapping);
-        }
+        // targets like `i, x` get unpacked like in an assignment
+        let mut target_result = vm.assign_target(executors, target, mapping);
+        changes.append(&mut target_result.changes);
+        dependencies.append(&mut target_result.dependencies);
 
         let result_mapping = Mapping::simple(Path::empty(), vm.knowledge().constant("None"));
 
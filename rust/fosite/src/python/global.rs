@@ -0,0 +1,44 @@
+use core::*;
+
+pub struct PythonGlobal { }
+
+impl GlobalExecutor for PythonGlobal {
+    fn execute(&self, env: Environment, names: &[String]) -> ExecutionResult {
+        let Environment { vm, .. } = env;
+
+        // declarations hold for the rest of the function
+        for name in names {
+            vm.last_scope_mut().declare_global(name.clone());
+        }
+
+        let result_mapping = Mapping::simple(Path::empty(), vm.knowledge().constant("None"));
+
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: vec!(),
+            changes: vec!(),
+            result: result_mapping,
+        }
+    }
+}
+
+pub struct PythonNonlocal { }
+
+impl NonlocalExecutor for PythonNonlocal {
+    fn execute(&self, env: Environment, names: &[String]) -> ExecutionResult {
+        let Environment { vm, .. } = env;
+
+        for name in names {
+            vm.last_scope_mut().declare_nonlocal(name.clone());
+        }
+
+        let result_mapping = Mapping::simple(Path::empty(), vm.knowledge().constant("None"));
+
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: vec!(),
+            changes: vec!(),
+            result: result_mapping,
+        }
+    }
+}
@@ -13,7 +13,7 @@ impl IdentifierExecutor for PythonIdentifier {
 
         let mut mapping = Mapping::new();
 
-        for scope in vm.scopes().rev() {
+        for scope in vm.lookup_scopes(name) {
             let opt_mappings = scope.resolve_optional_identifier(name);
 
             let mut new_unresolved = BTreeSet::new();
@@ -34,12 +34,20 @@ impl IdentifierExecutor for PythonIdentifier {
             }
 
             unresolved = new_unresolved;
-            if !unresolved.is_empty() {
+            if unresolved.is_empty() {
                 break;
             }
         }
         
-        if !unresolved.is_empty() {
+        // python raises an UnboundLocalError rather than looking further
+        if !unresolved.is_empty() && vm.is_local(name) {
+            let content = UnboundLocal::new(name.to_owned(), unresolved);
+            let message = Message::Output {
+                source: vm.current_node().clone(), 
+                content: Box::new(content),
+            };
+            CHANNEL.publish(message);
+        } else if !unresolved.is_empty() {
             let content = IdentifierInvalid::new(name.to_owned(), unresolved);
             let message = Message::Output {
                 source: vm.current_node().clone(), 
@@ -48,6 +56,7 @@ impl IdentifierExecutor for PythonIdentifier {
             CHANNEL.publish(message);
         }
 
+        vm.record_types(&mapping);
         vm.store_identifier_dependency(AnalysisItem::Identifier(name.to_owned()), &mapping);
 
         ExecutionResult {
@@ -1,5 +1,8 @@
 use core::*;
 
+use super::modules::constant_key;
+use super::modules::missing_key_paths;
+
 use std::collections::BTreeMap;
 use std::collections::btree_map::Entry;
 
@@ -15,47 +18,68 @@ impl IndexExecutor for PythonIndex {
                -> ExecutionResult{
         let Environment { vm, executors } = env;
 
+        let target_result = vm.execute(executors, target);
+        let mut result = self.index_mapping(vm, executors, target, &target_result.result, index);
+
+        let mut total_dependencies = target_result.dependencies;
+        let mut total_changes = target_result.changes;
+        total_dependencies.append(&mut result.dependencies);
+        total_changes.append(&mut result.changes);
+
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: total_dependencies,
+            changes: total_changes,
+            result: result.result,
+        }
+    }
+}
+
+impl PythonIndex {
+    // index a target that has been evaluated already
+    pub fn index_mapping(&self,
+                         vm: &mut VirtualMachine,
+                         executors: &Executors,
+                         target: &GastNode,
+                         target_mapping: &Mapping,
+                         index: &GastNode)
+                         -> ExecutionResult {
         // needed to add this indexation to the path
         let current_node = vm.current_node().clone();
 
         // mapping to return
         let mut result_mapping = Mapping::new();
 
-        let target_result = vm.execute(executors, target);
-
         // update dependencies and changes
         let mut total_dependencies = Vec::new();
         let mut total_changes = Vec::new();
 
-        let mut dependencies = target_result.dependencies;
-        let mut changes = target_result.changes;
-        total_dependencies.append(&mut dependencies);
-        total_changes.append(&mut changes);
-
         let index_result = vm.execute(executors, index);
         let mut dependencies = index_result.dependencies;
         let mut changes = index_result.changes;
         total_dependencies.append(&mut dependencies);
         total_changes.append(&mut changes);
 
-        // target object b here
-        let target_mapping = target_result.result;
-
         // index out of bounds warnings
         let mut warnings = Vec::new();
 
         // indexing something that doesn't support indexing
         let mut errors = BTreeMap::new();
 
-        for &(ref target_path, ref target_address) in &target_mapping {
+        // reading constant keys that were never inserted
+        let key = constant_key(index);
+        let mut missing = Vec::new();
+
+        for &(ref target_path, ref target_address) in target_mapping {
             // does this type of object support indexation?
             {
                 let kb = vm.knowledge();
                 let collection_type = kb.get_type(&"collection".to_owned()).unwrap();
                 let set_type = kb.get_type(&"set".to_owned()).unwrap();
+                let frozenset_type = kb.get_type("frozenset").unwrap();
                 let types = vm.ancestors(target_address);
                 
-                if !(types.contains(collection_type)) || types.contains(set_type) {
+                if !(types.contains(collection_type)) || types.contains(set_type) || types.contains(frozenset_type) {
                     let target_object = vm.get_object(target_address);
                     let type_name = target_object.get_type_name(kb);
 
@@ -81,6 +105,14 @@ impl IndexExecutor for PythonIndex {
                 let kb = vm.knowledge();
                 let dict_type = kb.get_type(&"dict".to_owned()).unwrap();
                 if target_object.get_extension().contains(dict_type) {
+                    if let Some(ref key) = key {
+                        for path in missing_key_paths(vm, target_address, key) {
+                            let mut new_path = target_path.clone();
+                            new_path.merge_into(path);
+                            missing.push(new_path);
+                        }
+                    }
+
                     let pls = target_object.get_attribute(&"___values".to_owned());
                     let &(_, ref new_address) = pls._iter().next().unwrap();
                     target_object = vm.get_object(&new_address.unwrap());
@@ -99,13 +131,18 @@ impl IndexExecutor for PythonIndex {
                         *value
                     };
 
-                    for (coll_path, _, max) in target_object.size_range() {
-                        if let Some(max) = max {
-                            if adjusted_value.abs() as usize > max {
-                                let mut new_path = target_path.clone();
-                                new_path.merge_into(coll_path);
-                                warnings.push((new_path, max as i16));
-                            }
+                    for (coll_path, min, max) in target_object.size_range() {
+                        // removed elements can leave a collection that might be empty
+                        let bound = match (min, max) {
+                            (_, Some(max)) if adjusted_value.unsigned_abs() as usize > max => Some(max),
+                            (Some(0), _) => Some(0),
+                            _ => None,
+                        };
+
+                        if let Some(bound) = bound {
+                            let mut new_path = target_path.clone();
+                            new_path.merge_into(coll_path);
+                            warnings.push((new_path, bound as i16));
                         }
                     }
 
@@ -138,6 +175,15 @@ impl IndexExecutor for PythonIndex {
             CHANNEL.publish(message);
         }
 
+        if !missing.is_empty() {
+            let content = KeyMissing::new(target.to_string(), key.unwrap(), missing);
+            let message = Message::Output {
+                source: vm.current_node().clone(),
+                content: Box::new(content),
+            };
+            CHANNEL.publish(message);
+        }
+
         if !errors.is_empty() {
             let content = IndexInvalid::new(target.to_string(), errors);
             let message = Message::Output {
@@ -1,5 +1,13 @@
 use core::*;
 
+use super::modules::constant_key;
+use super::modules::insert_key;
+use super::modules::check_hashable;
+use super::modules::new_dict;
+use super::modules::dict_keys;
+use super::modules::dict_values;
+use super::modules::new_string;
+
 pub struct PythonList {}
 
 impl ListExecutor for PythonList {
@@ -35,7 +43,12 @@ impl SetExecutor for PythonSet {
         let type_name = "set".to_owned();
         let obj_ptr = vm.object_of_type(&type_name);
 
-        make_collection(vm, executors, obj_ptr, content)
+        let result = make_collection(vm, executors, obj_ptr, content);
+
+        let elements = vm.get_object(&obj_ptr).get_any_element(vm.current_node());
+        check_hashable(vm, &type_name, &elements);
+
+        result
     }
 }
 
@@ -45,6 +58,10 @@ impl DictExecutor for PythonDict {
     fn execute(&self, env: Environment, content: &[GastNode] ) -> ExecutionResult {
         let Environment { vm, executors } = env;
 
+        if let Some(&GastNode { kind: NodeType::MapPair { ref source, ref key, ref value }, .. }) = content.first() {
+            return dict_from_comprehension(vm, executors, source, key, value);
+        }
+
         let dict_type = "dict".to_owned();
         let dict_ptr = vm.object_of_type(&dict_type);
 
@@ -60,7 +77,11 @@ impl DictExecutor for PythonDict {
 
         for node in content {
             if let NodeType::Pair {ref first, ref second} = node.kind {
+                insert_key(vm, &dict_ptr, constant_key(first), Path::empty());
+
                 let intermediate = vm.execute(executors, first);
+                check_hashable(vm, &dict_type, &intermediate.result);
+
                 let mut chunk = CollectionChunk::empty();
 
                 for (path, address) in intermediate.result{
@@ -135,36 +156,43 @@ pub struct PythonString { }
 
 impl StringExecutor for PythonString {
     fn execute(&self, env: Environment) -> ExecutionResult {
-        let Environment { mut vm, .. } = env;
-        let type_name = "str".to_owned();
-
-        let string_type = *vm.knowledge().get_type(&type_name).unwrap();
+        let Environment { vm, .. } = env;
 
-        let string_ptr = vm.object_of_type(&type_name);
-        let character_ptr = vm.object_of_type(&type_name);
+        let string_ptr = new_string(vm);
+        let mapping = Mapping::simple(Path::empty(), string_ptr);
 
-        {
-            let mut char_object = vm.get_object_mut(&character_ptr);
-            let repr = Representant::new(character_ptr, string_type, Some(1), Some(1));
-            let mut chunk = CollectionChunk::empty();
-            chunk.add_representant(Path::empty(), repr);
-            char_object.define_elements(vec!(chunk), Path::empty());
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: vec![],
+            changes: vec!(),
+            result: mapping,
         }
+    }
+}
 
-        {
-            let mut string_object = vm.get_object_mut(&string_ptr);
-            let repr = Representant::new(character_ptr, string_type, None, None);
-            let mut chunk = CollectionChunk::empty();
-            chunk.add_representant(Path::empty(), repr);
-            string_object.define_elements(vec!(chunk), Path::empty());
+pub struct PythonJoinedStr { }
+
+impl JoinedStrExecutor for PythonJoinedStr {
+    fn execute(&self, env: Environment, values: &[GastNode]) -> ExecutionResult {
+        let Environment { vm, executors } = env;
+
+        let mut total_changes = Vec::new();
+        let mut total_dependencies = Vec::new();
+
+        // every embedded expression gets evaluated, whatever its type
+        for value in values {
+            let mut value_result = vm.execute(executors, value);
+            total_changes.append(&mut value_result.changes);
+            total_dependencies.append(&mut value_result.dependencies);
         }
 
+        let string_ptr = new_string(vm);
         let mapping = Mapping::simple(Path::empty(), string_ptr);
 
         ExecutionResult {
             flow: FlowControl::Continue,
-            dependencies: vec![],
-            changes: vec!(),
+            dependencies: total_dependencies,
+            changes: total_changes,
             result: mapping,
         }
     }
@@ -244,27 +272,13 @@ fn collection_from_comprehension(
         1);
     path.add_node(node);
     vm.push_path(path);
+    vm.new_scope();
 
     let content_result = vm.execute(executors, content);
     let changes = content_result.changes;
     let dependencies = content_result.dependencies;
 
-    let mut chunk = CollectionChunk::empty();
-    for (path, address) in content_result.result {
-        let obj = vm.get_object(&address);
-
-        // todo, replace current node with the node of the generator
-        for (el_path, el_address) in obj.get_any_element(vm.current_node()) {
-            let mut new_path = path.clone();
-            for node in el_path {
-                new_path.add_node(node);
-            }
-
-            let kind = vm.get_object(&el_address).get_extension().first().unwrap();
-            let repr = Representant::new(el_address, *kind, Some(1), None);
-            chunk.add_representant(new_path, repr);  
-        }
-    }
+    let chunk = comprehension_chunk(vm, content_result.result);
 
     {
         let mut obj = vm.get_object_mut(&obj_ptr);
@@ -326,4 +340,79 @@ fn collection_from_literal(
         changes: changes,
         result: mapping,
     }
-}
\ No newline at end of file
+}
+
+// every value the comprehension produces can occur any number of times
+fn comprehension_chunk(vm: &VirtualMachine, values: Mapping) -> CollectionChunk {
+    let mut chunk = CollectionChunk::empty();
+
+    for (path, address) in values {
+        let kind = vm.get_object(&address).get_extension().first().unwrap();
+        let repr = Representant::new(address, *kind, Some(1), None);
+        chunk.add_representant(path, repr);
+    }
+
+    chunk
+}
+
+fn dict_from_comprehension(
+    vm: &mut VirtualMachine,
+    executors: &Executors,
+    source: &GastNode,
+    key: &GastNode,
+    value: &GastNode,
+) -> ExecutionResult {
+
+    let dict_type = "dict".to_owned();
+    let dict_ptr = new_dict(vm);
+
+    let mut path = vm.current_path().clone();
+    let node = PathNode::Frame(vm.current_node().clone(), 
+        Some("comprehension".to_owned()), 
+        0, 
+        1);
+    path.add_node(node);
+    vm.push_path(path);
+    vm.new_scope();
+
+    let source_result = vm.execute(executors, source);
+    let mut changes = source_result.changes;
+    let mut dependencies = source_result.dependencies;
+
+    let mut key_result = vm.execute(executors, key);
+    changes.append(&mut key_result.changes);
+    dependencies.append(&mut key_result.dependencies);
+    check_hashable(vm, &dict_type, &key_result.result);
+
+    let mut value_result = vm.execute(executors, value);
+    changes.append(&mut value_result.changes);
+    dependencies.append(&mut value_result.dependencies);
+
+    // the keys aren't known up front
+    insert_key(vm, &dict_ptr, None, Path::empty());
+
+    let key_chunk = comprehension_chunk(vm, key_result.result);
+    for keys_ptr in dict_keys(vm, &dict_ptr) {
+        let obj = vm.get_object_mut(&keys_ptr);
+        obj.define_elements(vec!(key_chunk.clone()), Path::empty());
+    }
+
+    let value_chunk = comprehension_chunk(vm, value_result.result);
+    for values_ptr in dict_values(vm, &dict_ptr) {
+        let obj = vm.get_object_mut(&values_ptr);
+        obj.define_elements(vec!(value_chunk.clone()), Path::empty());
+    }
+
+    vm.merge_function(&changes);
+
+    vm.pop_path();
+
+    let mapping = Mapping::simple(Path::empty(), dict_ptr);
+
+    ExecutionResult {
+        flow: FlowControl::Continue,
+        dependencies: dependencies,
+        changes: changes,
+        result: mapping,
+    }
+}
@@ -9,6 +9,8 @@ mod boolop;
 mod while_loop;
 mod break_loop;
 mod continue_loop;
+mod global;
+mod delete;
 mod index;
 mod generators;
 mod for_loop;
@@ -32,6 +34,8 @@ pub use self::boolop::*;
 pub use self::while_loop::*;
 pub use self::break_loop::*;
 pub use self::continue_loop::*;
+pub use self::global::*;
+pub use self::delete::*;
 pub use self::index::*;
 pub use self::generators::*;
 pub use self::for_loop::*;
@@ -1,6 +1,14 @@
 use core::*;
 
+use std::collections::BTreeSet;
+
 use super::check_arg;
+use super::ordinal;
+use super::check_hashable;
+use super::iterated_elements;
+use super::return_mapping;
+use super::new_string;
+use super::make_chunk;
 
 pub fn new_builtin_module() -> Module {
     let mut builtin = Module::new();
@@ -10,6 +18,8 @@ pub fn new_builtin_module() -> Module {
     define_str_cast(&mut builtin);
     define_list_cast(&mut builtin);
     define_tuple_cast(&mut builtin);
+    define_set_cast(&mut builtin, "set");
+    define_set_cast(&mut builtin, "frozenset");
     
     define_input(&mut builtin);
     define_print(&mut builtin);
@@ -20,6 +30,24 @@ pub fn new_builtin_module() -> Module {
     define_len(&mut builtin);
 
     define_range(&mut builtin);
+
+    define_enumerate(&mut builtin);
+    define_zip(&mut builtin);
+    define_reordering(&mut builtin, "sorted");
+    define_reordering(&mut builtin, "reversed");
+    define_map(&mut builtin);
+    define_filter(&mut builtin);
+    define_extreme(&mut builtin, "min");
+    define_extreme(&mut builtin, "max");
+    define_sum(&mut builtin);
+    define_truth_test(&mut builtin, "any");
+    define_truth_test(&mut builtin, "all");
+
+    define_isinstance(&mut builtin);
+    define_type(&mut builtin);
+    define_callable(&mut builtin);
+    define_hasattr(&mut builtin);
+    define_getattr(&mut builtin);
     
     builtin
 }
@@ -75,28 +103,7 @@ fn define_str_cast(module: &mut Module) {
                 check_arg(vm, &args[0], "first", vec!("object"));
             }
 
-            let type_name = "str".to_owned();
-
-            let string_type = *vm.knowledge().get_type(&type_name).unwrap();
-
-            let string_ptr = vm.object_of_type(&type_name);
-            let character_ptr = vm.object_of_type(&type_name);
-
-            {
-                let mut char_object = vm.get_object_mut(&character_ptr);
-                let repr = Representant::new(character_ptr, string_type, Some(1), Some(1));
-                let mut chunk = CollectionChunk::empty();
-                chunk.add_representant(Path::empty(), repr);
-                char_object.define_elements(vec!(chunk), Path::empty());
-            }
-
-            {
-                let mut string_object = vm.get_object_mut(&string_ptr);
-                let repr = Representant::new(character_ptr, string_type, None, None);
-                let mut chunk = CollectionChunk::empty();
-                chunk.add_representant(Path::empty(), repr);
-                string_object.define_elements(vec!(chunk), Path::empty());
-            }
+            let string_ptr = new_string(vm);
 
             let mapping = Mapping::simple(Path::empty(), string_ptr);
 
@@ -241,6 +248,66 @@ fn define_tuple_cast(module: &mut Module) {
     module.add_part("tuple".to_owned(), Box::new(outer));
 }
 
+// set and frozenset only differ in their methods
+fn define_set_cast(module: &mut Module, type_name: &'static str) {
+    let outer = move |vm: &mut VirtualMachine| {
+        let pointer = *vm.knowledge().get_type(type_name).unwrap();
+
+        let inner = move | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            let set_ptr = vm.object_of_type(type_name);
+
+            if !args.is_empty() {
+                check_arg(vm, &args[0], "first", vec!("collection"));
+
+                let mut elements = Mapping::new();
+                let mut chunk = CollectionChunk::empty();
+
+                for &(ref path, ref address) in &args[0] {
+                    for (element_path, element) in vm.get_object(address).get_any_element(vm.current_node()) {
+                        let mut new_path = element_path.clone();
+                        new_path.merge_into(path.clone());
+                        elements.add_mapping(new_path, element);
+                    }
+                }
+
+                check_hashable(vm, type_name, &elements);
+
+                // duplicates disappear, the size isn't known
+                for (path, element) in elements {
+                    let kind = *vm.get_object(&element).get_extension().first().unwrap();
+                    chunk.add_representant(path, Representant::new(element, kind, Some(0), None));
+                }
+
+                let set_object = vm.get_object_mut(&set_ptr);
+                set_object.define_elements(vec!(chunk), Path::empty());
+            } else {
+                // an empty set
+                vm.get_object_mut(&set_ptr).define_elements(vec!(), Path::empty());
+            }
+
+            let mapping = Mapping::simple(Path::empty(), set_ptr);
+
+            let path = vm.current_path().clone();
+            vm.add_result(path, mapping);
+
+            ExecutionResult {
+                flow: FlowControl::Continue,
+                dependencies: vec!(),
+                changes: vec!(),
+                result: Mapping::new(),
+            }
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+    
+    module.add_part(type_name.to_owned(), Box::new(outer));
+}
+
 fn define_float_cast(module: &mut Module) {
     let outer = |vm: &mut VirtualMachine| {
         let ptr = *vm.knowledge().get_type(&"float".to_owned()).unwrap();
@@ -289,28 +356,7 @@ fn define_input(module: &mut Module) {
                 check_arg(vm, &args[0], "first", vec!("object", "NoneType"));
             }
 
-            let type_name = "str".to_owned();
-
-            let string_type = *vm.knowledge().get_type(&type_name).unwrap();
-
-            let string_ptr = vm.object_of_type(&type_name);
-            let character_ptr = vm.object_of_type(&type_name);
-
-            {
-                let mut char_object = vm.get_object_mut(&character_ptr);
-                let repr = Representant::new(character_ptr, string_type, Some(1), Some(1));
-                let mut chunk = CollectionChunk::empty();
-                chunk.add_representant(Path::empty(), repr);
-                char_object.define_elements(vec!(chunk), Path::empty());
-            }
-
-            {
-                let mut string_object = vm.get_object_mut(&string_ptr);
-                let repr = Representant::new(character_ptr, string_type, None, None);
-                let mut chunk = CollectionChunk::empty();
-                chunk.add_representant(Path::empty(), repr);
-                string_object.define_elements(vec!(chunk), Path::empty());
-            }
+            let string_ptr = new_string(vm);
 
             let mapping = Mapping::simple(Path::empty(), string_ptr);
             
@@ -544,4 +590,688 @@ fn define_range(module: &mut Module) {
     };
 
     module.add_part("range".to_owned(), Box::new(outer));
-}
\ No newline at end of file
+}
+// the smallest and largest size of any of the given collections
+fn size_bounds(vm: &VirtualMachine, collections: &Mapping) -> (Option<usize>, Option<usize>) {
+    let mut min = None;
+    let mut max = Some(0);
+
+    for &(_, ref address) in collections {
+        for (_, branch_min, branch_max) in vm.get_object(address).size_range() {
+            min = match (min, branch_min) {
+                (None, new) => new,
+                (Some(old), Some(new)) => Some(::std::cmp::min(old, new)),
+                (Some(_), None) => Some(0),
+            };
+
+            max = max.and_then(|old| branch_max.map(|new| ::std::cmp::max(old, new)));
+        }
+    }
+
+    (min.or(Some(0)), max)
+}
+
+// every element is a possibility for a single place in a collection
+// elements on the same path are there at the same time, they can't share a chunk
+fn element_chunks(vm: &VirtualMachine, elements: &Mapping, min: Option<usize>, max: Option<usize>) -> Vec<CollectionChunk> {
+    // the minimum is only kept when the type is known
+    let min = if elements.len() == 1 { min } else { Some(0) };
+
+    let representants = elements.into_iter().map(|&(ref path, ref element)| {
+        let kind = *vm.get_object(element).get_extension().first().unwrap();
+        (path.clone(), Representant::new(*element, kind, min, max))
+    }).collect();
+
+    CollectionChunk::split(representants)
+}
+
+fn new_collection(vm: &mut VirtualMachine, type_name: &str, chunks: Vec<CollectionChunk>) -> Pointer {
+    let pointer = vm.object_of_type(type_name);
+
+    if !chunks.is_empty() {
+        vm.get_object_mut(&pointer).define_elements(chunks, Path::empty());
+    }
+
+    pointer
+}
+
+// a list with the given elements, as many as the given collections have
+fn new_list(vm: &mut VirtualMachine, elements: &Mapping, bounds: (Option<usize>, Option<usize>)) -> Pointer {
+    if elements.is_empty() {
+        return new_collection(vm, "list", vec!());
+    }
+
+    let (min, max) = bounds;
+    let chunks = element_chunks(vm, elements, min, max);
+    new_collection(vm, "list", chunks)
+}
+
+// a tuple with a single element for every mapping
+fn new_tuple(vm: &mut VirtualMachine, parts: &[Mapping]) -> Pointer {
+    let chunks = parts
+        .iter()
+        .map(|part| make_chunk(vm, part, Some(1), Some(1)))
+        .collect();
+
+    new_collection(vm, "tuple", chunks)
+}
+
+fn define_enumerate(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("function");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            let mut result = Mapping::new();
+
+            if let Some(iterable) = args.first() {
+                check_arg(vm, iterable, "first", vec!("collection"));
+
+                let elements = iterated_elements(vm, iterable);
+                let bounds = size_bounds(vm, iterable);
+
+                let mut pairs = Mapping::new();
+
+                if !elements.is_empty() {
+                    let int_ptr = vm.object_of_type("int");
+                    let index = Mapping::simple(Path::empty(), int_ptr);
+                    let tuple_ptr = new_tuple(vm, &[index, elements]);
+                    pairs.add_mapping(Path::empty(), tuple_ptr);
+                }
+
+                let list_ptr = new_list(vm, &pairs, bounds);
+                result.add_mapping(Path::empty(), list_ptr);
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("enumerate".to_owned(), Box::new(outer));
+}
+
+fn define_zip(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("function");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            let mut parts = Vec::new();
+            let mut min = None;
+            let mut max = None;
+
+            for iterable in &args {
+                check_arg(vm, iterable, "every", vec!("collection"));
+
+                parts.push(iterated_elements(vm, iterable));
+
+                // zip stops at the shortest collection
+                let (new_min, new_max) = size_bounds(vm, iterable);
+                min = match min {
+                    Some(old) => Some(::std::cmp::min(old, new_min.unwrap_or(0))),
+                    None => new_min,
+                };
+                max = match (max, new_max) {
+                    (Some(old), Some(new)) => Some(::std::cmp::min(old, new)),
+                    (old, new) => old.or(new),
+                };
+            }
+
+            let mut tuples = Mapping::new();
+
+            if !parts.is_empty() && parts.iter().all(|part| !part.is_empty()) {
+                let tuple_ptr = new_tuple(vm, &parts);
+                tuples.add_mapping(Path::empty(), tuple_ptr);
+            }
+
+            let list_ptr = new_list(vm, &tuples, (min, max));
+            return_mapping(vm, Mapping::simple(Path::empty(), list_ptr), vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("zip".to_owned(), Box::new(outer));
+}
+
+// sorted and reversed both keep the elements, their order isn't tracked
+fn define_reordering(module: &mut Module, name: &'static str) {
+    let outer = move |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("function");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            let mut result = Mapping::new();
+
+            if let Some(iterable) = args.first() {
+                check_arg(vm, iterable, "first", vec!("collection"));
+
+                let elements = iterated_elements(vm, iterable);
+                let bounds = size_bounds(vm, iterable);
+
+                let list_ptr = new_list(vm, &elements, bounds);
+                result.add_mapping(Path::empty(), list_ptr);
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part(name.to_owned(), Box::new(outer));
+}
+
+fn define_map(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("function");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, executors } = env;
+
+            let mut changes = Vec::new();
+            let mut values = Mapping::new();
+            let mut bounds = (Some(0), None);
+
+            if args.len() > 1 {
+                let mut elements = Vec::new();
+
+                for (index, iterable) in args.iter().enumerate().skip(1) {
+                    check_arg(vm, iterable, &ordinal(index), vec!("collection"));
+                    elements.push(iterated_elements(vm, iterable));
+                }
+
+                bounds = size_bounds(vm, &args[1]);
+
+                if elements.iter().all(|part| !part.is_empty()) {
+                    for &(ref path, ref function) in &args[0] {
+                        let result = vm.call_function(executors, function, "map", elements.clone());
+                        changes.extend(result.changes);
+
+                        for (result_path, address) in result.result {
+                            let mut new_path = path.clone();
+                            new_path.merge_into(result_path);
+                            values.add_mapping(new_path, address);
+                        }
+                    }
+                }
+            }
+
+            let list_ptr = new_list(vm, &values, bounds);
+            return_mapping(vm, Mapping::simple(Path::empty(), list_ptr), changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("map".to_owned(), Box::new(outer));
+}
+
+fn define_filter(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("function");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, executors } = env;
+
+            let mut changes = Vec::new();
+            let mut elements = Mapping::new();
+            let mut max = None;
+
+            if args.len() > 1 {
+                check_arg(vm, &args[1], "second", vec!("collection"));
+
+                elements = iterated_elements(vm, &args[1]);
+                max = size_bounds(vm, &args[1]).1;
+
+                let none = vm.knowledge().constant("None");
+
+                if !elements.is_empty() {
+                    for &(_, ref function) in &args[0] {
+                        // filter(None, ...) keeps the truthy elements
+                        if *function == none {
+                            continue;
+                        }
+
+                        let result = vm.call_function(executors, function, "filter", vec!(elements.clone()));
+                        changes.extend(result.changes);
+                    }
+                }
+            }
+
+            // any element could be filtered out
+            let list_ptr = new_list(vm, &elements, (Some(0), max));
+            return_mapping(vm, Mapping::simple(Path::empty(), list_ptr), changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("filter".to_owned(), Box::new(outer));
+}
+
+// min and max take a single collection or several arguments
+fn define_extreme(module: &mut Module, name: &'static str) {
+    let outer = move |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("function");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            let mut result = Mapping::new();
+
+            if args.len() == 1 {
+                check_arg(vm, &args[0], "first", vec!("collection"));
+                result = iterated_elements(vm, &args[0]);
+            } else {
+                for arg in &args {
+                    for &(ref path, ref address) in arg {
+                        result.add_mapping(path.clone(), *address);
+                    }
+                }
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part(name.to_owned(), Box::new(outer));
+}
+
+fn define_sum(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("function");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            let mut elements = Mapping::new();
+
+            if let Some(iterable) = args.first() {
+                check_arg(vm, iterable, "first", vec!("collection"));
+
+                elements = iterated_elements(vm, iterable);
+
+                // sum(["a", "b"]) is a type error
+                check_arg(vm, &elements, "elements of the first", vec!("number"));
+            }
+
+            if let Some(start) = args.get(1) {
+                check_arg(vm, start, "second", vec!("number"));
+
+                for &(ref path, ref address) in start {
+                    elements.add_mapping(path.clone(), *address);
+                }
+            }
+
+            // the sum is only an integer when everything is
+            let int_type = *vm.knowledge().get_type("int").unwrap();
+            let integral = elements
+                ._iter()
+                .all(|&(_, ref address)| vm.ancestors(address).contains(&int_type));
+
+            let type_name = if integral { "int" } else { "float" };
+            let number_ptr = vm.object_of_type(type_name);
+
+            return_mapping(vm, Mapping::simple(Path::empty(), number_ptr), vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("sum".to_owned(), Box::new(outer));
+}
+
+// any and all only look at the truth of the elements
+fn define_truth_test(module: &mut Module, name: &'static str) {
+    let outer = move |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("function");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            if let Some(iterable) = args.first() {
+                check_arg(vm, iterable, "first", vec!("collection"));
+            }
+
+            let bool_ptr = vm.object_of_type("bool");
+            return_mapping(vm, Mapping::simple(Path::empty(), bool_ptr), vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part(name.to_owned(), Box::new(outer));
+}
+
+// true and false are constants, so conditions can use them
+fn truth(vm: &VirtualMachine, value: bool) -> Pointer {
+    if value {
+        vm.knowledge().constant("True")
+    } else {
+        vm.knowledge().constant("False")
+    }
+}
+
+// the types in the second argument of isinstance, tuples hold several
+fn isinstance_types(vm: &VirtualMachine, address: &Pointer) -> Option<Vec<Pointer>> {
+    let object = vm.get_object(address);
+
+    if object.is_type() {
+        return Some(vec!(*address));
+    }
+
+    if vm.is_instance(address, "tuple") {
+        let mut types = Vec::new();
+
+        for (_, element) in object.get_any_element(vm.current_node()) {
+            if !vm.get_object(&element).is_type() {
+                return None;
+            }
+
+            types.push(element);
+        }
+
+        return Some(types);
+    }
+
+    None
+}
+
+fn define_isinstance(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("function");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            let mut result = Mapping::new();
+
+            if args.len() < 2 {
+                let bool_ptr = vm.object_of_type("bool");
+                return return_mapping(vm, Mapping::simple(Path::empty(), bool_ptr), vec!());
+            }
+
+            for &(ref type_path, ref type_address) in &args[1] {
+                let types = isinstance_types(vm, type_address);
+
+                for &(ref path, ref address) in &args[0] {
+                    let mut new_path = path.clone();
+                    new_path.merge_into(type_path.clone());
+
+                    let answer = match types {
+                        Some(ref types) => {
+                            let ancestors = vm.ancestors(address);
+                            let kinds = vm.get_object(address).get_extension().clone();
+
+                            // an object of an abstract type could be any of its subtypes
+                            let unsure = types.iter().any(|t| {
+                                vm.ancestors(t).iter().any(|a| kinds.contains(a))
+                            });
+
+                            if types.iter().any(|t| ancestors.contains(t)) {
+                                truth(vm, true)
+                            } else if unsure {
+                                vm.object_of_type("bool")
+                            } else {
+                                truth(vm, false)
+                            }
+                        }
+                        None => vm.object_of_type("bool"),
+                    };
+
+                    result.add_mapping(new_path, answer);
+                }
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("isinstance".to_owned(), Box::new(outer));
+}
+
+fn define_type(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("function");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            let mut result = Mapping::new();
+
+            if let Some(arg) = args.first() {
+                for &(ref path, ref address) in arg {
+                    let kind = *vm.get_object(address).get_extension().first().unwrap();
+                    result.add_mapping(path.clone(), kind);
+                }
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("type".to_owned(), Box::new(outer));
+}
+
+fn define_callable(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("function");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            let mut result = Mapping::new();
+
+            if let Some(arg) = args.first() {
+                for &(ref path, ref address) in arg {
+                    let value = vm.is_callable(address) || vm.get_object(address).is_type();
+                    result.add_mapping(path.clone(), truth(vm, value));
+                }
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("callable".to_owned(), Box::new(outer));
+}
+
+// the attribute of the object on every path, the types are used
+// when the object itself doesn't have it
+fn find_attribute(vm: &mut VirtualMachine, executors: &Executors, object: &Pointer, name: &str) 
+    -> Vec<(Path, Option<Pointer>)> {
+    let mut attribute: Vec<_> = vm.get_object(object).get_attribute(name)._iter().cloned().collect();
+
+    if attribute.is_empty() {
+        attribute.push((Path::empty(), None));
+    }
+
+    let mut result = Vec::new();
+
+    for (path, opt_address) in attribute {
+        let address = match opt_address {
+            Some(address) => Some(address),
+            None => type_attribute(vm, object, name).map(|address| {
+                if vm.is_instance(&address, "function") {
+                    vm.make_method_object(executors, object, &address)
+                } else {
+                    address
+                }
+            }),
+        };
+
+        result.push((path, address));
+    }
+
+    result
+}
+
+fn type_attribute(vm: &VirtualMachine, object: &Pointer, name: &str) -> Option<Pointer> {
+    for tpe in vm.ancestors(object) {
+        for &(_, ref opt_address) in vm.get_object(&tpe).get_attribute(name) {
+            if let Some(address) = *opt_address {
+                return Some(address);
+            }
+        }
+    }
+
+    None
+}
+
+// the constant name of an attribute, these can be looked up
+fn attribute_name(vm: &mut VirtualMachine, args: &[Mapping]) -> Option<String> {
+    let name = args.get(1)?;
+
+    check_arg(vm, name, "second", vec!("str"));
+
+    let mut names = name._iter().map(|&(_, ref address)| vm.string_value(address));
+    let first = names.next().and_then(|x| x).cloned();
+
+    if names.all(|other| other == first.as_ref()) {
+        first
+    } else {
+        None
+    }
+}
+
+fn define_hasattr(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("function");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, executors } = env;
+
+            let mut result = Mapping::new();
+
+            if let Some(name) = attribute_name(vm, &args) {
+                for &(ref path, ref address) in &args[0] {
+                    for (attribute_path, opt_address) in find_attribute(vm, executors, address, &name) {
+                        if path.mergeable(&attribute_path) {
+                            let mut new_path = path.clone();
+                            new_path.merge_into(attribute_path);
+                            result.add_mapping(new_path, truth(vm, opt_address.is_some()));
+                        }
+                    }
+                }
+            } else {
+                let bool_ptr = vm.object_of_type("bool");
+                result.add_mapping(Path::empty(), bool_ptr);
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("hasattr".to_owned(), Box::new(outer));
+}
+
+fn define_getattr(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("function");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, executors } = env;
+
+            let mut result = Mapping::new();
+            let mut missing = BTreeSet::new();
+            let default = args.get(2);
+            let name = attribute_name(vm, &args);
+
+            if let Some(ref name) = name {
+                for &(ref path, ref address) in &args[0] {
+                    for (attribute_path, opt_address) in find_attribute(vm, executors, address, name) {
+                        if !path.mergeable(&attribute_path) {
+                            continue;
+                        }
+
+                        let mut new_path = path.clone();
+                        new_path.merge_into(attribute_path);
+
+                        match (opt_address, default) {
+                            (Some(address), _) => result.add_mapping(new_path, address),
+                            (None, Some(default)) => {
+                                for &(ref default_path, ref default_address) in default {
+                                    let mut default_path = default_path.clone();
+                                    default_path.merge_into(new_path.clone());
+                                    result.add_mapping(default_path, *default_address);
+                                }
+                            }
+                            (None, None) => {
+                                missing.insert(new_path);
+                            }
+                        }
+                    }
+                }
+            } else {
+                // any attribute could be the result
+                let object_ptr = vm.object_of_type("object");
+                result.add_mapping(Path::empty(), object_ptr);
+
+                if let Some(default) = default {
+                    for &(ref path, ref address) in default {
+                        result.add_mapping(path.clone(), *address);
+                    }
+                }
+            }
+
+            // only a constant name can be missing
+            // the message takes the name of the object from the call
+            if !missing.is_empty() {
+                let content = AttributeInvalid::new(String::new(), name.unwrap(), missing);
+                let message = Message::Output {
+                    source: vm.current_node().clone(),
+                    content: Box::new(content),
+                };
+                CHANNEL.publish(message);
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("getattr".to_owned(), Box::new(outer));
+}
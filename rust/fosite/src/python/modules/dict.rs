@@ -0,0 +1,475 @@
+use core::*;
+
+use super::check_arg;
+use super::return_mapping;
+use super::check_hashable;
+use super::any_elements;
+use super::make_chunk;
+
+// a dict is modelled as a set of keys and a set of values
+// constant keys are remembered as attributes, so reading them can be checked
+pub fn new_dict_module() -> Module {
+    let mut dict = Module::new();
+    define_get(&mut dict);
+    define_keys(&mut dict);
+    define_values(&mut dict);
+    define_items(&mut dict);
+    define_setdefault(&mut dict);
+    define_pop(&mut dict);
+    define_update(&mut dict);
+    define_clear(&mut dict);
+    dict
+}
+
+// an empty dict, with its key and value sets
+pub fn new_dict(vm: &mut VirtualMachine) -> Pointer {
+    let dict_ptr = vm.object_of_type("dict");
+    let keys_ptr = vm.object_of_type("set");
+    let values_ptr = vm.object_of_type("set");
+
+    let obj = vm.get_object_mut(&dict_ptr);
+    obj.assign_attribute("___keys".to_owned(), Path::empty(), Mapping::simple(Path::empty(), keys_ptr));
+    obj.assign_attribute("___values".to_owned(), Path::empty(), Mapping::simple(Path::empty(), values_ptr));
+
+    dict_ptr
+}
+
+pub fn dict_keys(vm: &VirtualMachine, dict: &Pointer) -> Vec<Pointer> {
+    dict_part(vm, dict, "___keys")
+}
+
+pub fn dict_values(vm: &VirtualMachine, dict: &Pointer) -> Vec<Pointer> {
+    dict_part(vm, dict, "___values")
+}
+
+fn dict_part(vm: &VirtualMachine, dict: &Pointer, name: &str) -> Vec<Pointer> {
+    vm.get_object(dict)
+        .get_attribute(name)
+        ._iter()
+        .filter_map(|&(_, address)| address)
+        .collect()
+}
+
+// the key or value sets of the given dicts, on the paths of those dicts
+fn part_mapping(vm: &VirtualMachine, dicts: &Mapping, name: &str) -> Mapping {
+    let mut mapping = Mapping::new();
+
+    for &(ref path, ref dict) in dicts {
+        for part in dict_part(vm, dict, name) {
+            mapping.add_mapping(path.clone(), part);
+        }
+    }
+
+    mapping
+}
+
+// the key as it would be written in python, 1 and True are the same key
+pub fn constant_key(node: &GastNode) -> Option<String> {
+    match node.kind {
+        NodeType::String { ref value } => Some(format!("{:?}", value)),
+        NodeType::Int { ref value } => Some(format!("{}", value)),
+        NodeType::Boolean { ref value } => Some(format!("{}", *value as i64)),
+        _ => None,
+    }
+}
+
+// remember which keys got inserted on which path
+// an unknown key could be any key
+pub fn insert_key(vm: &mut VirtualMachine, dict: &Pointer, key: Option<String>, path: Path) {
+    let name = match key {
+        Some(key) => format!("___key {}", key),
+        None => "___unknown_keys".to_owned(),
+    };
+
+    let mapping = Mapping::simple(Path::empty(), vm.knowledge().constant("True"));
+    let obj = vm.get_object_mut(dict);
+    obj.assign_attribute(name, path, mapping);
+}
+
+// a deleted key is missing again from then on
+pub fn remove_key(vm: &mut VirtualMachine, dict: &Pointer, key: &str, path: Path, cause: PathNode) {
+    let mut mapping = OptionalMapping::new();
+    mapping.add_mapping(Path::empty(), None);
+    let mapping = mapping.augment(cause);
+
+    let obj = vm.get_object_mut(dict);
+    obj.assign_opt_attribute(format!("___key {}", key), path, mapping);
+}
+
+// any key and value of the other dicts could end up in these dicts
+pub fn update_dict(vm: &mut VirtualMachine, this: &Mapping, other: &Mapping) -> Vec<AnalysisItem> {
+    let current_path = vm.current_path().clone();
+    let mut changes = Vec::new();
+
+    let key_mapping = any_elements(vm, &part_mapping(vm, other, "___keys"));
+    let value_mapping = any_elements(vm, &part_mapping(vm, other, "___values"));
+    let key_chunk = make_chunk(vm, &key_mapping, Some(0), Some(1));
+    let value_chunk = make_chunk(vm, &value_mapping, Some(0), Some(1));
+
+    for &(_, ref dict) in this {
+        for address in dict_keys(vm, dict) {
+            vm.get_object_mut(&address).append_element(key_chunk.clone(), current_path.clone());
+            changes.push(AnalysisItem::Object(address));
+        }
+
+        for address in dict_values(vm, dict) {
+            vm.get_object_mut(&address).append_element(value_chunk.clone(), current_path.clone());
+            changes.push(AnalysisItem::Object(address));
+        }
+
+        insert_key(vm, dict, None, current_path.clone());
+        changes.push(AnalysisItem::Object(*dict));
+    }
+
+    changes
+}
+
+// the paths on which a constant key was never inserted
+pub fn missing_key_paths(vm: &VirtualMachine, dict: &Pointer, key: &str) -> Vec<Path> {
+    let obj = vm.get_object(dict);
+    let known = obj.get_attribute(&format!("___key {}", key));
+    let unknown = obj.get_attribute("___unknown_keys");
+
+    let mut paths = Vec::new();
+
+    for &(ref key_path, ref key_address) in known {
+        if key_address.is_some() {
+            continue;
+        }
+
+        for &(ref unknown_path, ref unknown_address) in unknown {
+            if unknown_address.is_none() && key_path.mergeable(unknown_path) {
+                let mut new_path = key_path.clone();
+                new_path.merge_into(unknown_path.clone());
+                paths.push(new_path);
+            }
+        }
+    }
+
+    paths
+}
+
+// a new set with all the elements of the given collections
+fn copy_elements(vm: &mut VirtualMachine, collections: &[Pointer]) -> Pointer {
+    let mut content = Collection::new();
+
+    for collection in collections {
+        content = content.concatenate(vm.get_object(collection).get_elements());
+    }
+
+    let set_ptr = vm.object_of_type("set");
+    vm.get_object_mut(&set_ptr).set_elements(content);
+    set_ptr
+}
+
+fn argument(args: &[Mapping], kwargs: &[(String, Mapping)], index: usize, name: &str) -> Option<Mapping> {
+    if let Some(arg) = args.get(index) {
+        return Some(arg.clone());
+    }
+
+    kwargs.iter()
+        .find(|&&(ref kwarg, _)| kwarg == name)
+        .map(|&(_, ref mapping)| mapping.clone())
+}
+
+fn define_get(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, kwargs: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("dict"));
+
+            let mut result = any_elements(vm, &part_mapping(vm, &args[0], "___values"));
+
+            // the default is returned when the key is missing
+            match argument(&args, &kwargs, 2, "default") {
+                Some(default) => {
+                    for (path, address) in default {
+                        result.add_mapping(path, address);
+                    }
+                }
+                None => result.add_mapping(Path::empty(), vm.knowledge().constant("None")),
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("get".to_owned(), Box::new(outer));
+}
+
+fn define_keys(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("dict"));
+
+            let mut result = Mapping::new();
+
+            for &(ref path, ref dict) in &args[0] {
+                let keys = dict_keys(vm, dict);
+                let set_ptr = copy_elements(vm, &keys);
+                result.add_mapping(path.clone(), set_ptr);
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("keys".to_owned(), Box::new(outer));
+}
+
+fn define_values(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("dict"));
+
+            let mut result = Mapping::new();
+
+            for &(ref path, ref dict) in &args[0] {
+                let values = dict_values(vm, dict);
+                let set_ptr = copy_elements(vm, &values);
+                result.add_mapping(path.clone(), set_ptr);
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("values".to_owned(), Box::new(outer));
+}
+
+fn define_items(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("dict"));
+
+            let mut result = Mapping::new();
+
+            for &(ref path, ref dict) in &args[0] {
+                let dict_mapping = Mapping::simple(Path::empty(), *dict);
+                let key_mapping = any_elements(vm, &part_mapping(vm, &dict_mapping, "___keys"));
+                let value_mapping = any_elements(vm, &part_mapping(vm, &dict_mapping, "___values"));
+
+                // (key, value) pairs, which key goes with which value is lost
+                let tuple_ptr = vm.object_of_type("tuple");
+                let key_chunk = make_chunk(vm, &key_mapping, Some(1), Some(1));
+                let value_chunk = make_chunk(vm, &value_mapping, Some(1), Some(1));
+                vm.get_object_mut(&tuple_ptr).define_elements(vec!(key_chunk, value_chunk), Path::empty());
+
+                let tuple_kind = *vm.knowledge().get_type("tuple").unwrap();
+                let mut chunk = CollectionChunk::empty();
+                chunk.add_representant(Path::empty(), Representant::new(tuple_ptr, tuple_kind, None, None));
+
+                let set_ptr = vm.object_of_type("set");
+                vm.get_object_mut(&set_ptr).define_elements(vec!(chunk), Path::empty());
+
+                result.add_mapping(path.clone(), set_ptr);
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("items".to_owned(), Box::new(outer));
+}
+
+fn define_setdefault(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, kwargs: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("dict"));
+
+            let current_path = vm.current_path().clone();
+            let none = vm.knowledge().constant("None");
+
+            let key = argument(&args, &kwargs, 1, "key").unwrap_or_else(|| Mapping::simple(Path::empty(), none));
+            let default = argument(&args, &kwargs, 2, "default").unwrap_or_else(|| Mapping::simple(Path::empty(), none));
+
+            check_hashable(vm, "dict", &key);
+
+            let key_chunk = make_chunk(vm, &key, Some(0), Some(1));
+            let value_chunk = make_chunk(vm, &default, Some(0), Some(1));
+
+            // the existing value, or the default
+            let mut result = any_elements(vm, &part_mapping(vm, &args[0], "___values"));
+            let mut changes = Vec::new();
+
+            for &(ref default_path, ref address) in &default {
+                result.add_mapping(default_path.clone(), *address);
+            }
+
+            for &(_, ref dict) in &args[0] {
+                let keys = dict_keys(vm, dict);
+                let values = dict_values(vm, dict);
+
+                for address in keys {
+                    vm.get_object_mut(&address).append_element(key_chunk.clone(), current_path.clone());
+                    changes.push(AnalysisItem::Object(address));
+                }
+
+                for address in values {
+                    vm.get_object_mut(&address).append_element(value_chunk.clone(), current_path.clone());
+                    changes.push(AnalysisItem::Object(address));
+                }
+
+                insert_key(vm, dict, None, current_path.clone());
+                changes.push(AnalysisItem::Object(*dict));
+            }
+
+            return_mapping(vm, result, changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("setdefault".to_owned(), Box::new(outer));
+}
+
+fn define_pop(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, kwargs: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("dict"));
+
+            // removing the key isn't modelled, it could be any key
+            let mut result = any_elements(vm, &part_mapping(vm, &args[0], "___values"));
+
+            if let Some(default) = argument(&args, &kwargs, 2, "default") {
+                for (path, address) in default {
+                    result.add_mapping(path, address);
+                }
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("pop".to_owned(), Box::new(outer));
+}
+
+fn define_update(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("dict"));
+
+            let mut changes = Vec::new();
+
+            if let Some(other) = args.get(1) {
+                check_arg(vm, other, "second", vec!("dict"));
+
+                changes = update_dict(vm, &args[0], other);
+            }
+
+            let none = vm.knowledge().constant("None");
+            return_mapping(vm, Mapping::simple(Path::empty(), none), changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("update".to_owned(), Box::new(outer));
+}
+
+fn define_clear(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("dict"));
+
+            let current_path = vm.current_path().clone();
+            let mut changes = Vec::new();
+
+            for &(ref path, ref dict) in &args[0] {
+                let mut parts = dict_keys(vm, dict);
+                parts.append(&mut dict_values(vm, dict));
+
+                for address in parts {
+                    vm.get_object_mut(&address).define_elements(vec!(), current_path.clone());
+                    changes.push(AnalysisItem::Object(address));
+                }
+
+                // every key is missing again, even the unknown ones
+                let keys: Vec<String> = vm.get_object(dict).attribute_names().into_iter()
+                    .filter(|name| name.starts_with("___key "))
+                    .map(|name| name["___key ".len()..].to_owned())
+                    .collect();
+
+                for key in keys {
+                    let cause = PathNode::Deletion(vm.current_node().clone(), format!("key {}", key));
+                    remove_key(vm, dict, &key, current_path.clone(), cause);
+                }
+
+                let mut unknown = OptionalMapping::new();
+                unknown.add_mapping(Path::empty(), None);
+                vm.get_object_mut(dict).assign_opt_attribute("___unknown_keys".to_owned(),
+                                                             current_path.clone(),
+                                                             unknown);
+
+                let mut new_path = path.clone();
+                new_path.merge_into(current_path.clone());
+                vm.store_object_change(*dict, &new_path);
+                changes.push(AnalysisItem::Object(*dict));
+            }
+
+            let none = vm.knowledge().constant("None");
+            return_mapping(vm, Mapping::simple(Path::empty(), none), changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("clear".to_owned(), Box::new(outer));
+}
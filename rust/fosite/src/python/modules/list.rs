@@ -1,171 +1,506 @@
 use core::*;
 
 use super::check_arg;
+use super::return_mapping;
+use super::make_chunk;
+use super::call_target;
+use super::check_empty_pop;
 
 pub fn new_list_module() -> Module {
     let mut list = Module::new();
     define_append(&mut list);
+    define_extend(&mut list);
+    define_insert(&mut list);
+    define_pop(&mut list);
+    define_remove(&mut list);
+    define_index(&mut list);
+    define_count(&mut list);
+    define_sort(&mut list);
     define_reverse(&mut list);
+    define_clear(&mut list);
+    define_copy(&mut list);
     list
 }
 
-fn define_reverse(module: &mut Module) {
+// code in a loop could be executed any number of times
+// the frame of this method call doesn't count as a function boundary
+fn in_loop(vm: &VirtualMachine) -> bool {
+    for node in vm.current_path()._iter().rev() {
+        match *node {
+            PathNode::Loop(_) => return true,
+            PathNode::Frame(ref location, _, _, _) if location != vm.current_node() => return false,
+            _ => ()
+        }
+    }
+
+    false
+}
+
+// how often an added element ends up in the collection
+pub fn added_bounds(vm: &VirtualMachine) -> (Option<usize>, Option<usize>) {
+    if in_loop(vm) {
+        (Some(0), None)
+    } else {
+        (Some(1), Some(1))
+    }
+}
+
+// all the elements of the given collections as a single chunk
+// there's no chunk when the collections are empty
+pub fn collection_chunk(vm: &VirtualMachine, mapping: &Mapping) -> Option<CollectionChunk> {
+    let mut elements = Mapping::new();
+    let mut min = None;
+    let mut max = Some(0);
+
+    for &(ref path, ref address) in mapping {
+        let object = vm.get_object(address);
+
+        for (_, branch_min, branch_max) in object.size_range() {
+            min = match (min, branch_min) {
+                (None, new) => new,
+                (Some(old), Some(new)) => Some(::std::cmp::min(old, new)),
+                (Some(_), None) => Some(0),
+            };
+
+            max = max.and_then(|old| branch_max.map(|new| ::std::cmp::max(old, new)));
+        }
+
+        for (element_path, element) in object.get_any_element(vm.current_node()) {
+            let mut new_path = path.clone();
+            new_path.merge_into(element_path);
+            elements.add_mapping(new_path, element);
+        }
+    }
+
+    if elements.is_empty() {
+        return None;
+    }
+
+    if in_loop(vm) {
+        min = Some(0);
+        max = None;
+    }
+
+    // every representant could make up the entire chunk
+    // the minimum is only kept when the type is known
+    if elements.len() != 1 {
+        min = Some(0);
+    }
+
+    let mut chunk = CollectionChunk::empty();
+
+    for (path, element) in elements {
+        let kind = *vm.get_object(&element).get_extension().first().unwrap();
+        chunk.add_representant(path, Representant::new(element, kind, min.or(Some(0)), max));
+    }
+
+    Some(chunk)
+}
+
+// change the elements of every list and remember the change
+fn update_elements<F>(vm: &mut VirtualMachine, this: &Mapping, update: F) -> Vec<AnalysisItem>
+    where F: Fn(&mut Object, Path) {
+    let current_path = vm.current_path().clone();
+    let mut changes = Vec::new();
+
+    for &(ref path, ref address) in this {
+        let mut new_path = current_path.clone();
+        new_path.merge_into(path.clone());
+
+        vm.store_object_change(*address, &new_path);
+
+        update(vm.get_object_mut(address), current_path.clone());
+        changes.push(AnalysisItem::Object(*address));
+    }
+
+    changes
+}
+
+// add elements, lists should keep a single type
+fn add_elements<F>(vm: &mut VirtualMachine, this: &Mapping, update: F) -> Vec<AnalysisItem>
+    where F: Fn(&mut Object, Path) {
+    let original_types = collection_types(vm, this);
+    let changes = update_elements(vm, this, update);
+    let target = call_target(vm, "list");
+
+    check_new_types(vm, &target, this, original_types);
+
+    changes
+}
+
+// remember the type of the collections before an addition
+pub fn collection_types(vm: &VirtualMachine, this: &Mapping) -> Vec<String> {
+    let mut types = Vec::new();
+
+    for &(_, ref address) in this {
+        types.push(vm.get_object(address).get_type_name(vm.knowledge()));
+    }
+
+    types
+}
+
+// check whether or not an element of a new type had been added
+pub fn check_new_types(vm: &VirtualMachine, target: &str, this: &Mapping, original_types: Vec<String>) {
+    for (&(_, ref address), original_type) in this._iter().zip(original_types) {
+        let new_type = vm.get_object(address).get_type_name(vm.knowledge());
+
+        if !new_type.contains(&original_type) {
+            let content = HeteroCollection::new(target.to_owned(), original_type, new_type);
+            let message = Message::Output {
+                source: vm.current_node().clone(),
+                content: Box::new(content),
+            };
+            CHANNEL.publish(message);
+        }
+    }
+}
+
+fn define_append(module: &mut Module) {
     let outer = |vm: &mut VirtualMachine| {
-        let pointer = *vm.knowledge().get_type(&"list".to_owned()).unwrap();
+        let pointer = vm.object_of_type("method");
 
         let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
-            let total_changes = Vec::new();
-            let total_dependencies = Vec::new();
-
             let Environment { vm, .. } = env;
 
-            if !args.is_empty() {
-                check_arg(vm, &args[0], "first", vec!("collection"));
+            let mut changes = Vec::new();
+
+            if args.len() == 2 {
+                check_arg(vm, &args[0], "first", vec!("list"));
+                check_arg(vm, &args[1], "second", vec!("object"));
+
+                let (min, max) = added_bounds(vm);
+                let chunk = make_chunk(vm, &args[1], min, max);
+
+                changes = add_elements(vm, &args[0], |object, path| {
+                    object.append_element(chunk.clone(), path);
+                });
             }
 
-            let type_name = "list".to_owned();
-            let list_ptr = vm.object_of_type(&type_name);
+            let none = vm.knowledge().constant("None");
+            return_mapping(vm, Mapping::simple(Path::empty(), none), changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
 
-            let mut content = Vec::new();
+    module.add_part("append".to_owned(), Box::new(outer));
+}
+
+fn define_extend(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
 
-            for mapping in &args {
-                for &(ref path, ref address) in mapping {
-                    let old_object = vm.get_object(address);
-                    let elements = old_object.get_elements().get_content();
-                    for collection_mapping in elements.iter().rev() {
-                        let mut new_path = collection_mapping.path.clone();
-                        new_path.merge_into(path.clone());
-                        content.push((new_path, collection_mapping.branch.clone()));
-                    }
+            let mut changes = Vec::new();
+
+            if args.len() == 2 {
+                check_arg(vm, &args[0], "first", vec!("list"));
+                check_arg(vm, &args[1], "second", vec!("collection"));
+
+                // extending with an empty collection changes nothing
+                if let Some(chunk) = collection_chunk(vm, &args[1]) {
+                    changes = add_elements(vm, &args[0], |object, path| {
+                        object.append_element(chunk.clone(), path);
+                    });
                 }
             }
 
-            let mut collection = Collection::new();
-            collection.set_content(content);
+            let none = vm.knowledge().constant("None");
+            return_mapping(vm, Mapping::simple(Path::empty(), none), changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("extend".to_owned(), Box::new(outer));
+}
+
+fn define_insert(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
 
-            {
-                let mut list_object = vm.get_object_mut(&list_ptr);
-                list_object.set_elements(collection);
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            let mut changes = Vec::new();
+
+            if args.len() == 3 {
+                check_arg(vm, &args[0], "first", vec!("list"));
+                check_arg(vm, &args[1], "second", vec!("int"));
+                check_arg(vm, &args[2], "third", vec!("object"));
+
+                let (min, max) = added_bounds(vm);
+                let chunk = make_chunk(vm, &args[2], min, max);
+
+                // the index isn't known, the element could end up anywhere
+                changes = add_elements(vm, &args[0], |object, path| {
+                    object.insert_element(chunk.clone(), path);
+                });
             }
 
-            let mapping = Mapping::simple(Path::empty(), list_ptr);
+            let none = vm.knowledge().constant("None");
+            return_mapping(vm, Mapping::simple(Path::empty(), none), changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("insert".to_owned(), Box::new(outer));
+}
+
+fn define_pop(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("list"));
 
-            let path = vm.current_path().clone();
-            vm.add_result(path, mapping);
+            // without an index the last element is taken
+            let index = match args.get(1) {
+                Some(index) => {
+                    check_arg(vm, index, "second", vec!("int"));
+                    None
+                },
+                None => Some(-1),
+            };
 
-            ExecutionResult {
-                flow: FlowControl::Continue,
-                dependencies: total_dependencies,
-                changes: total_changes,
-                result: Mapping::new(),
+            check_empty_pop(vm, &args[0], "list");
+
+            let current_node = vm.current_node().clone();
+            let mut result = Mapping::new();
+
+            for &(ref path, ref address) in &args[0] {
+                let object = vm.get_object(address);
+
+                let mut elements = match index {
+                    Some(index) => object.get_element(index, &current_node),
+                    None => Mapping::new(),
+                };
+
+                if elements.is_empty() {
+                    elements = object.get_any_element(&current_node);
+                }
+
+                for (element_path, element) in elements {
+                    let mut new_path = path.clone();
+                    new_path.merge_into(element_path);
+                    result.add_mapping(new_path, element);
+                }
             }
+
+            let changes = update_elements(vm, &args[0], |object, path| {
+                object.remove_element(index, path);
+            });
+
+            return_mapping(vm, result, changes)
         };
 
         vm.set_callable(pointer, inner);
 
         pointer
     };
-    
-    module.add_part("reverse".to_owned(), Box::new(outer));
+
+    module.add_part("pop".to_owned(), Box::new(outer));
 }
 
-fn define_append(module: &mut Module) {
+fn define_remove(module: &mut Module) {
     let outer = |vm: &mut VirtualMachine| {
         let pointer = vm.object_of_type(&"method".to_owned());
 
         let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
             let Environment { vm, .. } = env;
 
+            let mut changes = Vec::new();
+
             if args.len() == 2 {
                 check_arg(vm, &args[0], "first", vec!("list"));
-                check_arg(vm, &args[1], "second", vec!("object"));
 
-                let current_path = vm.current_path().clone();
-                let this = &args[0];
-                let chunk = make_chunk(vm, &args[1]);
-
-                for &(_, ref target_address) in this {
-                    // remember the type of the collection before the addition
-                    let original_type;
-                    {
-                        let parent_object = vm.get_object(target_address);
-                        let kb = vm.knowledge();
-                        original_type = parent_object.get_type_name(kb);
-                    }
-
-                    // add the new element
-                    {
-                        let mut parent_object = vm.get_object_mut(target_address);
-
-                        // add the new element
-                        parent_object.append_element(chunk.clone(), current_path.clone());
-                    }
-
-                    // get the new type of the
-                    let new_type;
-                    {
-                        let parent_object = vm.get_object(target_address);
-                        let kb = vm.knowledge();
-                        new_type = parent_object.get_type_name(kb);
-                    }
-
-                    // check whether or not an element of a new type had been added
-                    if !new_type.contains(&original_type) {
-                        let content = HeteroCollection::new("object".to_owned(), original_type, new_type);
-                        let message = Message::Output {
-                            source: vm.current_node().clone(),
-                            content: Box::new(content),
-                        };
-                        CHANNEL.publish(message);
-                    }
-                }
+                // which element gets removed isn't known
+                changes = update_elements(vm, &args[0], |object, path| {
+                    object.remove_element(None, path);
+                });
             }
-            
-            let mapping = Mapping::simple(Path::empty(), vm.knowledge().constant("None"));
 
-            let path = vm.current_path().clone();
-            vm.add_result(path, mapping);
+            let none = vm.knowledge().constant("None");
+            return_mapping(vm, Mapping::simple(Path::empty(), none), changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("remove".to_owned(), Box::new(outer));
+}
+
+fn define_index(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("list"));
+
+            let int_ptr = vm.object_of_type("int");
+            return_mapping(vm, Mapping::simple(Path::empty(), int_ptr), vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
 
-            ExecutionResult {
-                flow: FlowControl::Continue,
-                dependencies: vec!(),
-                changes: vec!(),
-                result: Mapping::new(),
+    module.add_part("index".to_owned(), Box::new(outer));
+}
+
+fn define_count(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("list"));
+
+            let int_ptr = vm.object_of_type("int");
+            return_mapping(vm, Mapping::simple(Path::empty(), int_ptr), vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("count".to_owned(), Box::new(outer));
+}
+
+fn define_sort(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("list"));
+
+            // the new order isn't known, the elements and size stay the same
+            // only loops over the list have to know about it
+            let current_path = vm.current_path().clone();
+
+            for &(ref path, ref address) in &args[0] {
+                let mut new_path = current_path.clone();
+                new_path.merge_into(path.clone());
+                vm.store_object_change(*address, &new_path);
             }
+
+            let none = vm.knowledge().constant("None");
+            return_mapping(vm, Mapping::simple(Path::empty(), none), vec!())
         };
 
         vm.set_callable(pointer, inner);
 
         pointer
     };
-    
-    module.add_part("append".to_owned(), Box::new(outer));
+
+    module.add_part("sort".to_owned(), Box::new(outer));
+}
+
+fn define_reverse(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("list"));
+
+            let changes = update_elements(vm, &args[0], |object, path| {
+                object.reverse_elements(path);
+            });
+
+            let none = vm.knowledge().constant("None");
+            return_mapping(vm, Mapping::simple(Path::empty(), none), changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("reverse".to_owned(), Box::new(outer));
 }
 
+fn define_clear(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
 
-fn make_chunk(vm: &VirtualMachine, mapping: &Mapping) -> CollectionChunk {
-    let mut chunk = CollectionChunk::empty();
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
 
-    let mut max = Some(1);
-    for node in vm.current_path()._iter().rev() {
-        match *node {
-            PathNode::Loop(_) => {
-                max = None;
-                break;
-            },
-            PathNode::Frame(_, _, _, _) => {
-                break;
-            },
-            _ => ()
-        }
-    }
+            check_arg(vm, &args[0], "first", vec!("list"));
 
-    for &(ref path, ref pointer) in mapping {
-        let value_obj = vm.get_object(pointer);
-        let kind = value_obj.get_extension().first().unwrap();
+            let changes = update_elements(vm, &args[0], |object, path| {
+                object.define_elements(vec!(), path);
+            });
 
-        chunk.add_representant(path.clone(), Representant::new(*pointer, *kind, Some(0), max));
-    }
+            let none = vm.knowledge().constant("None");
+            return_mapping(vm, Mapping::simple(Path::empty(), none), changes)
+        };
 
-    chunk
-}
\ No newline at end of file
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("clear".to_owned(), Box::new(outer));
+}
+
+fn define_copy(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("list"));
+
+            let mut result = Mapping::new();
+
+            // a new object with the same elements, not an alias
+            for &(ref path, ref address) in &args[0] {
+                let mut content = Vec::new();
+
+                for collection_mapping in vm.get_object(address).get_elements().get_content() {
+                    content.push((collection_mapping.path.clone(), collection_mapping.branch.clone()));
+                }
+
+                let mut collection = Collection::new();
+                collection.set_content(content);
+
+                let list_ptr = vm.object_of_type("list");
+                vm.get_object_mut(&list_ptr).set_elements(collection);
+
+                result.add_mapping(path.clone(), list_ptr);
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("copy".to_owned(), Box::new(outer));
+}
@@ -3,22 +3,60 @@ mod cmath;
 mod builtin;
 mod string;
 mod list;
+mod dict;
+mod set;
+mod stubs;
 
 pub use self::math::*;
 pub use self::builtin::*;
 pub use self::string::*;
 pub use self::cmath::*;
 pub use self::list::*;
+pub use self::dict::*;
+pub use self::set::*;
+pub use self::stubs::*;
 
 use core::VirtualMachine;
+use core::AnalysisItem;
+use core::ExecutionResult;
+use core::FlowControl;
 use core::Mapping;
 use core::Message;
 use core::ArgInvalid;
+use core::EmptyPop;
+use core::HashInvalid;
+use core::Path;
+use core::PathNode;
+use core::CollectionChunk;
+use core::Representant;
 use core::CHANNEL;
 
 use std::collections::HashSet;
+use std::collections::BTreeMap;
 
-fn check_arg(vm: &mut VirtualMachine, arg: &Mapping, index: &'static str, permitted: Vec<&'static str>) {
+// the position of an argument, for the messages
+fn ordinal(index: usize) -> String {
+    match index {
+        0 => "first".to_owned(),
+        1 => "second".to_owned(),
+        2 => "third".to_owned(),
+        3 => "fourth".to_owned(),
+        4 => "fifth".to_owned(),
+        n => {
+            let number = n + 1;
+            let suffix = match (number % 10, number % 100) {
+                (1, hundreds) if hundreds != 11 => "st",
+                (2, hundreds) if hundreds != 12 => "nd",
+                (3, hundreds) if hundreds != 13 => "rd",
+                _ => "th",
+            };
+
+            format!("{}{}", number, suffix)
+        }
+    }
+}
+
+fn check_arg(vm: &mut VirtualMachine, arg: &Mapping, index: &str, permitted: Vec<&str>) {
     let permitted_ptr: HashSet<_> = permitted
         .iter()
         .map(|x| *vm.knowledge().get_type(&x.to_string()).unwrap_or(&0))
@@ -47,4 +85,172 @@ fn check_arg(vm: &mut VirtualMachine, arg: &Mapping, index: &'static str, permit
             content: Box::new(content)};
         CHANNEL.publish(message);
     }
-}
\ No newline at end of file
+}
+
+// the result of a method, these are collected by the call
+fn return_mapping(vm: &mut VirtualMachine, mapping: Mapping, changes: Vec<AnalysisItem>) -> ExecutionResult {
+    let path = vm.current_path().clone();
+    vm.add_result(path, mapping);
+
+    ExecutionResult {
+        flow: FlowControl::Continue,
+        dependencies: vec!(),
+        changes,
+        result: Mapping::new(),
+    }
+}
+
+// the elements of a for loop over these collections, dicts iterate their keys
+fn iterated_elements(vm: &VirtualMachine, collections: &Mapping) -> Mapping {
+    let current_node = vm.current_node().clone();
+    let mut elements = Mapping::new();
+
+    for &(ref path, ref address) in collections {
+        let parts = if vm.is_instance(address, &"dict".to_owned()) {
+            dict_keys(vm, address)
+        } else {
+            vec!(*address)
+        };
+
+        for part in parts {
+            for (element_path, element) in vm.get_object(&part).get_any_element(&current_node) {
+                let mut new_path = path.clone();
+                new_path.merge_into(element_path);
+                elements.add_mapping(new_path, element);
+            }
+        }
+    }
+
+    elements
+}
+
+// every element of the given collections
+fn any_elements(vm: &VirtualMachine, collections: &Mapping) -> Mapping {
+    let mut result = Mapping::new();
+
+    for &(ref path, ref address) in collections {
+        for (element_path, element) in vm.get_object(address).get_any_element(vm.current_node()) {
+            let mut new_path = path.clone();
+            new_path.merge_into(element_path);
+            result.add_mapping(new_path, element);
+        }
+    }
+
+    result
+}
+
+// every value is a possibility for the same place in a collection
+fn make_chunk(vm: &VirtualMachine, mapping: &Mapping, min: Option<usize>, max: Option<usize>) -> CollectionChunk {
+    let mut chunk = CollectionChunk::empty();
+
+    for &(ref path, ref address) in mapping {
+        let kind = *vm.get_object(address).get_extension().first().unwrap();
+        chunk.add_representant(path.clone(), Representant::new(*address, kind, min, max));
+    }
+
+    chunk
+}
+
+// the name of the collection the method got called on, for the messages
+fn call_target(vm: &VirtualMachine, default: &str) -> String {
+    for node in vm.current_path()._iter().rev() {
+        if let PathNode::Frame(_, Some(ref name), _, _) = *node {
+            if let Some(index) = name.rfind('.') {
+                return name[..index].to_owned();
+            }
+        }
+    }
+
+    default.to_owned()
+}
+
+// popping from a collection that might be empty
+fn check_empty_pop(vm: &VirtualMachine, collections: &Mapping, default: &str) {
+    let mut empty = Vec::new();
+
+    for &(ref path, ref address) in collections {
+        for (coll_path, min, _) in vm.get_object(address).size_range() {
+            if min == Some(0) {
+                let mut new_path = path.clone();
+                new_path.merge_into(coll_path);
+                empty.push(new_path);
+            }
+        }
+    }
+
+    if !empty.is_empty() {
+        let content = EmptyPop::new(call_target(vm, default), empty);
+        let message = Message::Output {
+            source: vm.current_node().clone(),
+            content: Box::new(content),
+        };
+        CHANNEL.publish(message);
+    }
+}
+
+// arguments unpacked with * or **, which parameters they fill isn't known
+pub fn is_unpacked(name: &str) -> bool {
+    name == "*" || name == "**"
+}
+
+pub fn has_unpacked(kwargs: &[(String, Mapping)]) -> bool {
+    kwargs.iter().any(|&(ref name, _)| is_unpacked(name))
+}
+
+// any value an unpacked argument could give to a parameter
+pub fn unpacked_elements(vm: &VirtualMachine, kwargs: &[(String, Mapping)]) -> Mapping {
+    let mut result = Mapping::new();
+
+    for &(ref name, ref mapping) in kwargs {
+        let elements = if name == "*" {
+            any_elements(vm, mapping)
+        } else if name == "**" {
+            let mut values = Mapping::new();
+
+            for &(ref path, ref dict) in mapping {
+                for address in dict_values(vm, dict) {
+                    values.add_mapping(path.clone(), address);
+                }
+            }
+
+            any_elements(vm, &values)
+        } else {
+            continue;
+        };
+
+        for (path, address) in elements {
+            result.add_mapping(path, address);
+        }
+    }
+
+    result
+}
+
+// lists, dicts and sets can't be set elements or dict keys
+pub fn check_hashable(vm: &mut VirtualMachine, target: &str, elements: &Mapping) {
+    let unhashable: HashSet<_> = ["mutable_sequence", "dict", "set"]
+        .iter()
+        .map(|x| *vm.knowledge().get_type(&x.to_string()).unwrap())
+        .collect();
+
+    let mut problems: BTreeMap<String, Vec<Path>> = BTreeMap::new();
+
+    for &(ref path, ref address) in elements {
+        let types = vm.ancestors(address);
+        if types.iter().any(|t| unhashable.contains(t)) {
+            let type_name = vm.get_object(address).get_type_name(vm.knowledge());
+
+            let mut new_path = vm.current_path().clone();
+            new_path.merge_into(path.clone());
+            problems.entry(type_name).or_default().push(new_path);
+        }
+    }
+
+    if !problems.is_empty() {
+        let content = HashInvalid::new(target.to_owned(), problems);
+        let message = Message::Output { 
+            source: vm.current_node().clone(),
+            content: Box::new(content)};
+        CHANNEL.publish(message);
+    }
+}
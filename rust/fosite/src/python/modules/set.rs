@@ -0,0 +1,340 @@
+use core::*;
+
+use super::check_arg;
+use super::return_mapping;
+use super::check_hashable;
+use super::make_chunk;
+use super::any_elements;
+use super::added_bounds;
+use super::check_empty_pop;
+
+// elements that get removed aren't known, every element becomes optional
+pub fn new_set_module() -> Module {
+    let mut set = Module::new();
+    define_add(&mut set);
+    define_remove(&mut set);
+    define_discard(&mut set);
+    define_pop(&mut set);
+    define_update(&mut set);
+    define_union(&mut set);
+    define_intersection(&mut set);
+    define_difference(&mut set);
+    define_issubset(&mut set);
+    set
+}
+
+// frozensets only have the methods that don't change them
+pub fn new_frozenset_module() -> Module {
+    let mut frozenset = Module::new();
+    define_union(&mut frozenset);
+    define_intersection(&mut frozenset);
+    define_difference(&mut frozenset);
+    define_issubset(&mut frozenset);
+    frozenset
+}
+
+// a new object of the same type as the given set
+fn same_type(vm: &mut VirtualMachine, set: &Pointer) -> Pointer {
+    let kind = *vm.get_object(set).get_extension().first().unwrap();
+    let type_name = vm.knowledge().get_type_name(&kind).clone();
+    vm.object_of_type(&type_name)
+}
+
+// the same elements, none of them have to be there
+pub fn optional_elements(vm: &VirtualMachine, set: &Pointer) -> CollectionChunk {
+    let mut chunk = CollectionChunk::empty();
+
+    for (path, element) in vm.get_object(set).get_any_element(vm.current_node()) {
+        let kind = *vm.get_object(&element).get_extension().first().unwrap();
+        chunk.add_representant(path, Representant::new(element, kind, Some(0), None));
+    }
+
+    chunk
+}
+
+// any element could be gone afterwards
+fn forget_elements(vm: &mut VirtualMachine, this: &Mapping) -> Vec<AnalysisItem> {
+    let mut changes = Vec::new();
+    let current_path = vm.current_path().clone();
+
+    for &(ref path, ref address) in this {
+        let chunk = optional_elements(vm, address);
+
+        let mut new_path = current_path.clone();
+        new_path.merge_into(path.clone());
+
+        vm.store_object_change(*address, &new_path);
+        vm.get_object_mut(address).define_elements(vec!(chunk), current_path.clone());
+        changes.push(AnalysisItem::Object(*address));
+    }
+
+    changes
+}
+
+// add elements that might already be in the set
+fn add_elements(vm: &mut VirtualMachine, this: &Mapping, elements: &Mapping) -> Vec<AnalysisItem> {
+    check_hashable(vm, "set", elements);
+
+    let mut changes = Vec::new();
+    let current_path = vm.current_path().clone();
+    let (min, max) = added_bounds(vm);
+    let chunk = make_chunk(vm, elements, min, max);
+
+    for &(ref path, ref address) in this {
+        let mut new_path = current_path.clone();
+        new_path.merge_into(path.clone());
+
+        vm.store_object_change(*address, &new_path);
+        vm.get_object_mut(address).append_element(chunk.clone(), current_path.clone());
+        changes.push(AnalysisItem::Object(*address));
+    }
+
+    changes
+}
+
+fn define_add(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("set"));
+
+            let mut changes = Vec::new();
+
+            if let Some(element) = args.get(1) {
+                changes = add_elements(vm, &args[0], element);
+            }
+
+            let none = vm.knowledge().constant("None");
+            return_mapping(vm, Mapping::simple(Path::empty(), none), changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("add".to_owned(), Box::new(outer));
+}
+
+fn define_update(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("set"));
+
+            let mut changes = Vec::new();
+
+            for other in args.iter().skip(1) {
+                check_arg(vm, other, "second", vec!("collection"));
+
+                let elements = any_elements(vm, other);
+                changes.append(&mut add_elements(vm, &args[0], &elements));
+            }
+
+            let none = vm.knowledge().constant("None");
+            return_mapping(vm, Mapping::simple(Path::empty(), none), changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("update".to_owned(), Box::new(outer));
+}
+
+fn define_remove(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("set"));
+
+            let changes = forget_elements(vm, &args[0]);
+
+            let none = vm.knowledge().constant("None");
+            return_mapping(vm, Mapping::simple(Path::empty(), none), changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("remove".to_owned(), Box::new(outer));
+}
+
+fn define_discard(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("set"));
+
+            let changes = forget_elements(vm, &args[0]);
+
+            let none = vm.knowledge().constant("None");
+            return_mapping(vm, Mapping::simple(Path::empty(), none), changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("discard".to_owned(), Box::new(outer));
+}
+
+fn define_pop(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("set"));
+            check_empty_pop(vm, &args[0], "set");
+
+            let result = any_elements(vm, &args[0]);
+            let changes = forget_elements(vm, &args[0]);
+
+            return_mapping(vm, result, changes)
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("pop".to_owned(), Box::new(outer));
+}
+
+fn define_union(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("set", "frozenset"));
+
+            let mut result = Mapping::new();
+
+            for &(ref path, ref address) in &args[0] {
+                let mut elements = vm.get_object(address).get_elements().clone();
+
+                for other in args.iter().skip(1) {
+                    for &(_, ref other_address) in other {
+                        elements = elements.concatenate(vm.get_object(other_address).get_elements());
+                    }
+                }
+
+                let new_ptr = same_type(vm, address);
+                vm.get_object_mut(&new_ptr).set_elements(elements);
+                result.add_mapping(path.clone(), new_ptr);
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("union".to_owned(), Box::new(outer));
+}
+
+fn define_intersection(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("set", "frozenset"));
+
+            let mut result = Mapping::new();
+
+            // only keeps elements of the original, which ones isn't known
+            for &(ref path, ref address) in &args[0] {
+                let chunk = optional_elements(vm, address);
+                let new_ptr = same_type(vm, address);
+                vm.get_object_mut(&new_ptr).define_elements(vec!(chunk), Path::empty());
+                result.add_mapping(path.clone(), new_ptr);
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("intersection".to_owned(), Box::new(outer));
+}
+
+fn define_difference(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("set", "frozenset"));
+
+            let mut result = Mapping::new();
+
+            for &(ref path, ref address) in &args[0] {
+                let chunk = optional_elements(vm, address);
+                let new_ptr = same_type(vm, address);
+                vm.get_object_mut(&new_ptr).define_elements(vec!(chunk), Path::empty());
+                result.add_mapping(path.clone(), new_ptr);
+            }
+
+            return_mapping(vm, result, vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("difference".to_owned(), Box::new(outer));
+}
+
+fn define_issubset(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type("method");
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("set", "frozenset"));
+
+            if let Some(other) = args.get(1) {
+                check_arg(vm, other, "second", vec!("collection"));
+            }
+
+            let bool_ptr = vm.object_of_type("bool");
+            return_mapping(vm, Mapping::simple(Path::empty(), bool_ptr), vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("issubset".to_owned(), Box::new(outer));
+}
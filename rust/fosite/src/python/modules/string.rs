@@ -1,6 +1,11 @@
 use core::*;
 
 use super::check_arg;
+use super::return_mapping;
+use super::iterated_elements;
+use super::has_unpacked;
+
+use std::collections::BTreeMap;
 
 pub fn new_str_module() -> Module {
     let mut string = Module::new();
@@ -9,42 +14,92 @@ pub fn new_str_module() -> Module {
     define_upper(&mut string);
     define_lower(&mut string);
     define_isalpha(&mut string);
+    define_join(&mut string);
     string
 }
 
+// a string of unknown length, made up of single characters
+pub fn new_string(vm: &mut VirtualMachine) -> Pointer {
+    let type_name = "str".to_owned();
+
+    let string_type = *vm.knowledge().get_type(&type_name).unwrap();
+
+    let string_ptr = vm.object_of_type(&type_name);
+    let character_ptr = vm.object_of_type(&type_name);
+
+    {
+        let mut char_object = vm.get_object_mut(&character_ptr);
+        let repr = Representant::new(character_ptr, string_type, Some(1), Some(1));
+        let mut chunk = CollectionChunk::empty();
+        chunk.add_representant(Path::empty(), repr);
+        char_object.define_elements(vec!(chunk), Path::empty());
+    }
+
+    {
+        let mut string_object = vm.get_object_mut(&string_ptr);
+        let repr = Representant::new(character_ptr, string_type, None, None);
+        let mut chunk = CollectionChunk::empty();
+        chunk.add_representant(Path::empty(), repr);
+        string_object.define_elements(vec!(chunk), Path::empty());
+    }
+
+    string_ptr
+}
+
+// the amount of values a %-format string takes
+// none when it takes its values from a mapping
+pub fn placeholder_count(format: &str) -> Option<usize> {
+    let mut count = 0;
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+
+        match chars.peek() {
+            Some(&'%') => {
+                chars.next();
+                continue;
+            }
+            Some(&'(') => return None,
+            _ => (),
+        }
+
+        // flags, width and precision, a * width takes a value as well
+        while let Some(&c) = chars.peek() {
+            if !"#0- +*.123456789hlL".contains(c) {
+                break;
+            }
+
+            if c == '*' {
+                count += 1;
+            }
+
+            chars.next();
+        }
+
+        // the conversion type
+        chars.next();
+        count += 1;
+    }
+
+    Some(count)
+}
+
 fn define_format(module: &mut Module) {
     let outer = |vm: &mut VirtualMachine| {
         let pointer = vm.object_of_type(&"method".to_owned());
 
-        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+        let inner = | env: Environment, args: Vec<Mapping>, kwargs: Vec<(String, Mapping)> | {
             let Environment { vm, .. } = env;
 
             if !args.is_empty() {
                 check_arg(vm, &args[0], "first", vec!("object"));
+                check_format(vm, &args[0], &args[1..], &kwargs);
             }
 
-            let type_name = "str".to_owned();
-
-            let string_type = *vm.knowledge().get_type(&type_name).unwrap();
-
-            let string_ptr = vm.object_of_type(&type_name);
-            let character_ptr = vm.object_of_type(&type_name);
-
-            {
-                let mut char_object = vm.get_object_mut(&character_ptr);
-                let repr = Representant::new(character_ptr, string_type, Some(1), Some(1));
-                let mut chunk = CollectionChunk::empty();
-                chunk.add_representant(Path::empty(), repr);
-                char_object.define_elements(vec!(chunk), Path::empty());
-            }
-
-            {
-                let mut string_object = vm.get_object_mut(&string_ptr);
-                let repr = Representant::new(character_ptr, string_type, None, None);
-                let mut chunk = CollectionChunk::empty();
-                chunk.add_representant(Path::empty(), repr);
-                string_object.define_elements(vec!(chunk), Path::empty());
-            }
+            let string_ptr = new_string(vm);
 
             let mapping = Mapping::simple(Path::empty(), string_ptr);
 
@@ -67,6 +122,228 @@ fn define_format(module: &mut Module) {
     module.add_part("format".to_owned(), Box::new(outer));
 }
 
+// the argument a replacement field takes its value from
+#[derive(Debug, PartialEq)]
+enum FieldName {
+    Automatic,
+    Index(usize),
+    Keyword(String),
+}
+
+// a replacement field of a str.format string
+struct FormatField {
+    name: FieldName,
+    // fields like {0.real} or {0[1]} don't format the argument itself
+    direct: bool,
+    conversion: bool,
+    spec: String,
+}
+
+// the replacement fields in the order python numbers them
+fn format_fields(format: &str) -> Result<Vec<FormatField>, String> {
+    let mut fields = Vec::new();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+            }
+            '}' => return Err("Single } in the format string".to_owned()),
+            '{' => {
+                // the spec can hold nested fields
+                let mut content = String::new();
+                let mut depth = 1;
+
+                loop {
+                    let next = match chars.next() {
+                        Some(next) => next,
+                        None => return Err("Single { in the format string".to_owned()),
+                    };
+
+                    match next {
+                        '{' => depth += 1,
+                        '}' if depth == 1 => break,
+                        '}' => depth -= 1,
+                        _ => (),
+                    }
+
+                    content.push(next);
+                }
+
+                fields.append(&mut format_field(&content)?);
+            }
+            _ => (),
+        }
+    }
+
+    Ok(fields)
+}
+
+// a field followed by the fields nested in its spec
+fn format_field(content: &str) -> Result<Vec<FormatField>, String> {
+    let (head, spec) = match content.find(':') {
+        Some(index) => (&content[..index], &content[index + 1..]),
+        None => (content, ""),
+    };
+
+    let (field_name, conversion) = match head.find('!') {
+        Some(index) => (&head[..index], true),
+        None => (head, false),
+    };
+
+    let argument = field_name.split(|c| c == '.' || c == '[').next().unwrap();
+
+    let name = if argument.is_empty() {
+        FieldName::Automatic
+    } else if let Ok(index) = argument.parse() {
+        FieldName::Index(index)
+    } else {
+        FieldName::Keyword(argument.to_owned())
+    };
+
+    let mut fields = vec!(FormatField {
+        name: name,
+        direct: argument.len() == field_name.len(),
+        conversion: conversion,
+        spec: spec.to_owned(),
+    });
+
+    fields.append(&mut format_fields(spec)?);
+
+    Ok(fields)
+}
+
+// the type of the argument when the presentation type doesn't accept it
+fn presentation_mismatch(vm: &VirtualMachine, address: &Pointer, presentation: char) -> Option<String> {
+    let rejected = match presentation {
+        'b' | 'c' | 'd' | 'o' | 'x' | 'X' => {
+            vm.is_instance(address, "str") || vm.is_instance(address, "float")
+        }
+        'e' | 'E' | 'f' | 'F' | 'g' | 'G' | 'n' | '%' => vm.is_instance(address, "str"),
+        's' => vm.is_instance(address, "number"),
+        _ => false,
+    };
+
+    if rejected {
+        let kb = vm.knowledge();
+        Some(vm.get_object(address).get_type_name(kb))
+    } else {
+        None
+    }
+}
+
+// the fields of a constant format string need matching arguments
+fn check_format(vm: &VirtualMachine, this: &Mapping, args: &[Mapping], kwargs: &[(String, Mapping)]) {
+    let mut problems = BTreeMap::new();
+
+    // unpacked arguments could provide any field
+    let unpacked = has_unpacked(kwargs);
+
+    for &(ref path, ref address) in this {
+        let format = match vm.string_value(address) {
+            Some(format) => format,
+            None => continue,
+        };
+
+        let repr = format!("{:?}", format);
+
+        let fields = match format_fields(format) {
+            Ok(fields) => fields,
+            Err(problem) => {
+                problems.entry((repr, problem)).or_insert_with(Vec::new).push(path.clone());
+                continue;
+            }
+        };
+
+        let automatic = fields.iter().any(|field| field.name == FieldName::Automatic);
+        let manual = fields.iter().any(|field| match field.name {
+            FieldName::Index(_) => true,
+            _ => false,
+        });
+
+        if automatic && manual {
+            let problem = "Automatic and manual field numbering are mixed".to_owned();
+            problems.entry((repr, problem)).or_insert_with(Vec::new).push(path.clone());
+            continue;
+        }
+
+        let mut next = 0;
+
+        for field in &fields {
+            let (description, argument) = match field.name {
+                FieldName::Automatic | FieldName::Index(_) => {
+                    let index = match field.name {
+                        FieldName::Index(index) => index,
+                        _ => {
+                            next += 1;
+                            next - 1
+                        }
+                    };
+
+                    (format!("positional argument {}", index), args.get(index))
+                }
+                FieldName::Keyword(ref name) => {
+                    let argument = kwargs.iter()
+                        .find(|&&(ref keyword, _)| keyword == name)
+                        .map(|&(_, ref mapping)| mapping);
+
+                    (format!("keyword argument {}", name), argument)
+                }
+            };
+
+            let argument = match argument {
+                Some(argument) => argument,
+                None if unpacked => continue,
+                None => {
+                    let problem = format!("There is no {}", description);
+                    problems.entry((repr.clone(), problem)).or_insert_with(Vec::new).push(path.clone());
+                    continue;
+                }
+            };
+
+            // nested fields decide the presentation type at runtime
+            if !field.direct || field.conversion || field.spec.contains('{') {
+                continue;
+            }
+
+            let presentation = match field.spec.chars().last() {
+                Some(presentation) => presentation,
+                None => continue,
+            };
+
+            for &(ref argument_path, ref argument_address) in argument {
+                if !path.mergeable(argument_path) {
+                    continue;
+                }
+
+                if let Some(type_name) = presentation_mismatch(vm, argument_address, presentation) {
+                    let mut new_path = path.clone();
+                    new_path.merge_into(argument_path.clone());
+
+                    let problem = format!("The {} can be a {}, which can't be formatted with :{}",
+                                          description,
+                                          type_name,
+                                          presentation);
+                    problems.entry((repr.clone(), problem)).or_insert_with(Vec::new).push(new_path);
+                }
+            }
+        }
+    }
+
+    for ((format, problem), paths) in problems {
+        let content = FormatInvalid::new(format, problem, paths);
+        let message = Message::Output {
+            source: vm.current_node().clone(),
+            content: Box::new(content),
+        };
+        CHANNEL.publish(message);
+    }
+}
+
 fn define_find(module: &mut Module) {
     let outer = |vm: &mut VirtualMachine| {
         let pointer = vm.object_of_type(&"method".to_owned());
@@ -132,28 +409,7 @@ fn define_upper(module: &mut Module) {
         let inner = | env: Environment, _: Vec<Mapping>, _: Vec<(String, Mapping)> | {
             let Environment { vm, .. } = env;
 
-            let type_name = "str".to_owned();
-
-            let string_type = *vm.knowledge().get_type(&type_name).unwrap();
-
-            let string_ptr = vm.object_of_type(&type_name);
-            let character_ptr = vm.object_of_type(&type_name);
-
-            {
-                let mut char_object = vm.get_object_mut(&character_ptr);
-                let repr = Representant::new(character_ptr, string_type, Some(1), Some(1));
-                let mut chunk = CollectionChunk::empty();
-                chunk.add_representant(Path::empty(), repr);
-                char_object.define_elements(vec!(chunk), Path::empty());
-            }
-
-            {
-                let mut string_object = vm.get_object_mut(&string_ptr);
-                let repr = Representant::new(character_ptr, string_type, None, None);
-                let mut chunk = CollectionChunk::empty();
-                chunk.add_representant(Path::empty(), repr);
-                string_object.define_elements(vec!(chunk), Path::empty());
-            }
+            let string_ptr = new_string(vm);
 
             let mapping = Mapping::simple(Path::empty(), string_ptr);
 
@@ -183,28 +439,7 @@ fn define_lower(module: &mut Module) {
         let inner = | env: Environment, _: Vec<Mapping>, _: Vec<(String, Mapping)> | {
             let Environment { vm, .. } = env;
 
-            let type_name = "str".to_owned();
-
-            let string_type = *vm.knowledge().get_type(&type_name).unwrap();
-
-            let string_ptr = vm.object_of_type(&type_name);
-            let character_ptr = vm.object_of_type(&type_name);
-
-            {
-                let mut char_object = vm.get_object_mut(&character_ptr);
-                let repr = Representant::new(character_ptr, string_type, Some(1), Some(1));
-                let mut chunk = CollectionChunk::empty();
-                chunk.add_representant(Path::empty(), repr);
-                char_object.define_elements(vec!(chunk), Path::empty());
-            }
-
-            {
-                let mut string_object = vm.get_object_mut(&string_ptr);
-                let repr = Representant::new(character_ptr, string_type, None, None);
-                let mut chunk = CollectionChunk::empty();
-                chunk.add_representant(Path::empty(), repr);
-                string_object.define_elements(vec!(chunk), Path::empty());
-            }
+            let string_ptr = new_string(vm);
 
             let mapping = Mapping::simple(Path::empty(), string_ptr);
 
@@ -255,4 +490,88 @@ fn define_isalpha(module: &mut Module) {
     };
 
     module.add_part("isalpha".to_owned(), Box::new(outer));
-}
\ No newline at end of file
+}
+
+fn define_join(module: &mut Module) {
+    let outer = |vm: &mut VirtualMachine| {
+        let pointer = vm.object_of_type(&"method".to_owned());
+
+        let inner = | env: Environment, args: Vec<Mapping>, _: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            check_arg(vm, &args[0], "first", vec!("str"));
+
+            if let Some(iterable) = args.get(1) {
+                check_arg(vm, iterable, "first", vec!("collection"));
+
+                let elements = iterated_elements(vm, iterable);
+
+                // "".join(numbers) is a type error
+                check_arg(vm, &elements, "elements of the first", vec!("str"));
+            }
+
+            let string_ptr = new_string(vm);
+            return_mapping(vm, Mapping::simple(Path::empty(), string_ptr), vec!())
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part("join".to_owned(), Box::new(outer));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(format: &str) -> Vec<FieldName> {
+        format_fields(format).unwrap().into_iter().map(|field| field.name).collect()
+    }
+
+    #[test]
+    fn field_names() {
+        assert_eq!(names("{} {0} {name}"),
+                   vec![FieldName::Automatic, FieldName::Index(0), FieldName::Keyword("name".to_owned())]);
+    }
+
+    #[test]
+    fn escaped_braces() {
+        assert_eq!(names("{{}} {{0}}"), vec![]);
+        assert_eq!(names("{{{}}}"), vec![FieldName::Automatic]);
+    }
+
+    #[test]
+    fn conversion_and_spec() {
+        let fields = format_fields("{0!r:>10}").unwrap();
+
+        assert_eq!(fields.len(), 1);
+        assert!(fields[0].conversion);
+        assert!(fields[0].direct);
+        assert_eq!(fields[0].spec, ">10");
+    }
+
+    #[test]
+    fn attributes_and_items_are_not_direct() {
+        let fields = format_fields("{0.real} {x[1]} {y}").unwrap();
+        let direct: Vec<bool> = fields.iter().map(|field| field.direct).collect();
+
+        assert_eq!(direct, vec![false, false, true]);
+        assert_eq!(fields[1].name, FieldName::Keyword("x".to_owned()));
+    }
+
+    #[test]
+    fn nested_fields_follow_their_parent() {
+        assert_eq!(names("{:{}.{}} {}"),
+                   vec![FieldName::Automatic, FieldName::Automatic, FieldName::Automatic, FieldName::Automatic]);
+        assert_eq!(names("{a:{b}}"),
+                   vec![FieldName::Keyword("a".to_owned()), FieldName::Keyword("b".to_owned())]);
+    }
+
+    #[test]
+    fn single_braces_are_errors() {
+        assert_eq!(format_fields("{0").err(), Some("Single { in the format string".to_owned()));
+        assert_eq!(format_fields("0}").err(), Some("Single } in the format string".to_owned()));
+    }
+}
@@ -0,0 +1,322 @@
+use core::*;
+
+use rustc_serialize::json::Json;
+
+use super::check_arg;
+use super::new_dict;
+use super::insert_key;
+use super::has_unpacked;
+use super::ordinal;
+
+// signatures of builtin modules, functions and methods
+pub const CATALOGUE: &str = include_str!("../../../stubs/catalogue.json");
+
+#[derive(Clone, Debug)]
+pub enum Returns {
+    Nothing,
+    Type(String),
+    Collection(String, Box<Returns>),
+}
+
+#[derive(Clone, Debug)]
+pub struct Parameter {
+    pub name: String,
+    pub types: Vec<String>,
+    pub default: bool,
+    pub variadic: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct Signature {
+    pub params: Vec<Parameter>,
+    pub returns: Returns,
+    pub method: bool,
+}
+
+// stub format, all sections are optional
+//
+// {
+//   "math": {
+//     "functions": {
+//       "log": {
+//         "params": [{"name": "x", "types": ["number"]},
+//                    {"name": "base", "types": ["number"], "default": true}],
+//         "returns": "float"
+//       }
+//     },
+//     "methods": { ... },
+//     "constants": {"tau": "float"}
+//   }
+// }
+//
+// returns is a type name, "None", or {"type": "list", "elements": "str"}
+pub fn load_stubs(vm: &mut VirtualMachine, text: &str) -> Result<(), String> {
+    let json = Json::from_str(text).map_err(|why| format!("{}", why))?;
+
+    let modules = match json.as_object() {
+        Some(modules) => modules,
+        None => return Err("stubs should be an object of modules".to_owned()),
+    };
+
+    for (module_name, content) in modules {
+        let mut module = vm.retrieve_module(module_name).unwrap_or_default();
+
+        for &(section, method) in &[("functions", false), ("methods", true)] {
+            let functions = match content.find(section).and_then(|f| f.as_object()) {
+                Some(functions) => functions,
+                None => continue,
+            };
+
+            for (name, json) in functions {
+                let context = format!("{}.{}", module_name, name);
+                let signature = parse_signature(vm, json, method, &context)?;
+
+                // hand written definitions know more than a stub
+                if !module.has_part(name) {
+                    define_stub(&mut module, name, signature);
+                }
+            }
+        }
+
+        if let Some(constants) = content.find("constants").and_then(|c| c.as_object()) {
+            for (name, json) in constants {
+                let context = format!("{}.{}", module_name, name);
+                let returns = parse_returns(vm, json, &context)?;
+
+                if !module.has_part(name) {
+                    let outer = move |vm: &mut VirtualMachine| make_object(vm, &returns);
+                    module.add_part(name.clone(), Box::new(outer));
+                }
+            }
+        }
+
+        vm.insert_module(module_name.clone(), module);
+    }
+
+    Ok(())
+}
+
+fn parse_signature(vm: &VirtualMachine, json: &Json, method: bool, context: &str) -> Result<Signature, String> {
+    let mut params = Vec::new();
+
+    if let Some(json_params) = json.find("params") {
+        let json_params = match json_params.as_array() {
+            Some(json_params) => json_params,
+            None => return Err(format!("{}: params should be a list", context)),
+        };
+
+        for param in json_params {
+            let name = match param.find("name").and_then(|n| n.as_string()) {
+                Some(name) => name.to_owned(),
+                None => return Err(format!("{}: every parameter needs a name", context)),
+            };
+
+            let mut types = Vec::new();
+
+            if let Some(json_types) = param.find("types").and_then(|t| t.as_array()) {
+                for t in json_types {
+                    let t = t.as_string().unwrap_or("");
+                    check_type(vm, t, context)?;
+                    types.push(t.to_owned());
+                }
+            }
+
+            params.push(Parameter {
+                name,
+                types,
+                default: param.find("default").and_then(|d| d.as_boolean()).unwrap_or(false),
+                variadic: param.find("variadic").and_then(|v| v.as_boolean()).unwrap_or(false),
+            });
+        }
+    }
+
+    let returns = match json.find("returns") {
+        Some(returns) => parse_returns(vm, returns, context)?,
+        None => Returns::Nothing,
+    };
+
+    Ok(Signature {
+        params,
+        returns,
+        method,
+    })
+}
+
+fn parse_returns(vm: &VirtualMachine, json: &Json, context: &str) -> Result<Returns, String> {
+    if let Some(name) = json.as_string() {
+        if name == "None" {
+            return Ok(Returns::Nothing);
+        }
+
+        check_type(vm, name, context)?;
+        return Ok(Returns::Type(name.to_owned()));
+    }
+
+    let name = json.find("type").and_then(|t| t.as_string());
+    let elements = json.find("elements");
+
+    match (name, elements) {
+        (Some(name), Some(elements)) => {
+            check_type(vm, name, context)?;
+            let elements = parse_returns(vm, elements, context)?;
+            Ok(Returns::Collection(name.to_owned(), Box::new(elements)))
+        }
+        _ => Err(format!("{}: invalid return type {}", context, json)),
+    }
+}
+
+fn check_type(vm: &VirtualMachine, name: &str, context: &str) -> Result<(), String> {
+    if vm.knowledge().get_type(name).is_some() {
+        Ok(())
+    } else {
+        Err(format!("{}: unknown type {:?}", context, name))
+    }
+}
+
+// the signature the way python shows it, for messages
+fn describe(name: &str, signature: &Signature) -> String {
+    let parameters: Vec<String> = signature.params
+        .iter()
+        .map(|param| if param.variadic {
+            format!("*{}", param.name)
+        } else if param.default {
+            format!("{}=...", param.name)
+        } else {
+            param.name.clone()
+        })
+        .collect();
+
+    format!("{}({})", name, parameters.join(", "))
+}
+
+fn define_stub(module: &mut Module, name: &str, signature: Signature) {
+    let description = describe(name, &signature);
+
+    let outer = move |vm: &mut VirtualMachine| {
+        let type_name = if signature.method { "method" } else { "function" };
+        let pointer = vm.object_of_type(type_name);
+
+        let signature = signature.clone();
+        let description = description.clone();
+
+        let inner = move | env: Environment, args: Vec<Mapping>, kwargs: Vec<(String, Mapping)> | {
+            let Environment { vm, .. } = env;
+
+            // methods get their parent as the first argument
+            let offset = if signature.method { 1 } else { 0 };
+
+            for (index, param) in signature.params.iter().enumerate() {
+                if param.types.is_empty() {
+                    continue;
+                }
+
+                let permitted: Vec<&str> = param.types.iter().map(|t| t.as_str()).collect();
+
+                if param.variadic {
+                    for (position, arg) in args.iter().enumerate().skip(index + offset) {
+                        check_arg(vm, arg, &ordinal(position - offset), permitted.clone());
+                    }
+                } else if let Some(arg) = args.get(index + offset) {
+                    check_arg(vm, arg, &ordinal(index), permitted);
+                } else if let Some((_, arg)) = kwargs.iter().find(|(name, _)| *name == param.name) {
+                    check_arg(vm, arg, &param.name, permitted);
+                }
+            }
+
+            let mut problems = Vec::new();
+
+            // unpacked arguments could fill any parameter
+            let unpacked = has_unpacked(&kwargs);
+
+            for (index, param) in signature.params.iter().enumerate() {
+                if unpacked || param.default || param.variadic || args.len() > index + offset {
+                    continue;
+                }
+
+                if !kwargs.iter().any(|(name, _)| *name == param.name) {
+                    problems.push(format!("argument {} is missing", param.name));
+                }
+            }
+
+            let positional = args.len() - offset;
+            let variadic = signature.params.iter().any(|param| param.variadic);
+
+            if !unpacked && !variadic && positional > signature.params.len() {
+                problems.push(format!("{} positional arguments are given, but it takes at most {}",
+                    positional, signature.params.len()));
+            }
+
+            if !problems.is_empty() {
+                let content = ArityInvalid::new(description.clone(), problems, vm.current_path().clone());
+                let message = Message::Output {
+                    source: vm.current_node().clone(),
+                    content: Box::new(content),
+                };
+                CHANNEL.publish(message);
+            }
+
+            let pointer = make_object(vm, &signature.returns);
+
+            let mapping = Mapping::simple(Path::empty(), pointer);
+            let path = vm.current_path().clone();
+            vm.add_result(path, mapping);
+
+            ExecutionResult {
+                flow: FlowControl::Continue,
+                dependencies: vec!(),
+                changes: vec!(),
+                result: Mapping::new(),
+            }
+        };
+
+        vm.set_callable(pointer, inner);
+
+        pointer
+    };
+
+    module.add_part(name.to_owned(), Box::new(outer));
+}
+
+fn make_object(vm: &mut VirtualMachine, returns: &Returns) -> Pointer {
+    match *returns {
+        Returns::Nothing => vm.knowledge().constant("None"),
+        Returns::Type(ref type_name) if type_name == "str" => {
+            let character = vm.object_of_type(type_name);
+            make_collection(vm, type_name, character, Some(1))
+        }
+        // the keys of a returned dict aren't known
+        Returns::Type(ref type_name) if type_name == "dict" => {
+            let dict = new_dict(vm);
+            insert_key(vm, &dict, None, Path::empty());
+            dict
+        }
+        Returns::Type(ref type_name) => vm.object_of_type(type_name),
+        Returns::Collection(ref type_name, ref elements) => {
+            let element = make_object(vm, elements);
+            make_collection(vm, type_name, element, None)
+        }
+    }
+}
+
+// a collection of unknown size, strings consist of single characters
+fn make_collection(vm: &mut VirtualMachine, type_name: &str, element: Pointer, size: Option<usize>) -> Pointer {
+    let collection = vm.object_of_type(type_name);
+
+    if size.is_some() {
+        let kind = *vm.knowledge().get_type(type_name).unwrap();
+        let char_object = vm.get_object_mut(&element);
+        let repr = Representant::new(element, kind, size, size);
+        let mut chunk = CollectionChunk::empty();
+        chunk.add_representant(Path::empty(), repr);
+        char_object.define_elements(vec!(chunk), Path::empty());
+    }
+
+    let kind = *vm.get_object(&element).get_extension().first().unwrap();
+    let object = vm.get_object_mut(&collection);
+    let repr = Representant::new(element, kind, None, None);
+    let mut chunk = CollectionChunk::empty();
+    chunk.add_representant(Path::empty(), repr);
+    object.define_elements(vec!(chunk), Path::empty());
+
+    collection
+}
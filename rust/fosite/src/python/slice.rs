@@ -3,44 +3,96 @@ use core::*;
 pub struct PythonSlice { }
 
 impl SliceExecutor for PythonSlice {
-    fn execute(&self, env: Environment, value: &GastNode, lower_node: &GastNode, upper_node: &GastNode) -> ExecutionResult {
-        let Environment { mut vm, executors } = env;
+    fn execute(&self, 
+               env: Environment, 
+               value: &GastNode, 
+               lower_node: &GastNode, 
+               upper_node: &GastNode, 
+               step_node: &GastNode) 
+               -> ExecutionResult {
+        let Environment { vm, executors } = env;
 
         let value_result = vm.execute(executors, value);
+        let result = self.slice_mapping(vm, value, &value_result.result, lower_node, upper_node, step_node);
 
+        ExecutionResult {
+            flow: FlowControl::Continue,
+            dependencies: value_result.dependencies,
+            changes: value_result.changes,
+            result,
+        }
+    }
+}
+
+impl PythonSlice {
+    // slice a value that has been evaluated already
+    pub fn slice_mapping(&self,
+                         vm: &mut VirtualMachine,
+                         value: &GastNode,
+                         value_mapping: &Mapping,
+                         lower_node: &GastNode,
+                         upper_node: &GastNode,
+                         step_node: &GastNode)
+                         -> Mapping {
+        let mut step = match step_node.kind {
+            NodeType::Int {ref value} => Some(*value as i16),
+            _ => None,
+        };
+
+        if step == Some(0) {
+            let content = StepInvalid::new(value.to_string(), vm.current_path().clone());
+            let message = Message::Output { 
+                source: vm.current_node().clone(),
+                content: Box::new(content)};
+            CHANNEL.publish(message);
+
+            step = Some(1);
+        }
+
+        // the amount of elements that get dropped at the front and the back
+        // bounds that depend on the size of the collection don't drop any
         let lower;
         let upper;
 
-        match lower_node.kind {
-            NodeType::Int {ref value} => {
-                lower = *value as i16;
+        match step {
+            Some(n) if n > 0 => {
+                lower = match lower_node.kind {
+                    NodeType::Int {ref value} if *value >= 0 => *value as i16,
+                    _ => 0,
+                };
+
+                upper = match upper_node.kind {
+                    NodeType::Int {ref value} if *value < 0 => (*value * -1) as i16,
+                    _ => 0,
+                };
             },
-            _ => lower = 0,
-        }
+            // going backwards, the upper bound is at the front
+            Some(_) => {
+                lower = match upper_node.kind {
+                    NodeType::Int {ref value} if *value >= 0 => (*value + 1) as i16,
+                    _ => 0,
+                };
 
-        match upper_node.kind {
-            NodeType::Int {ref value} => {
-                if *value < 0 {
-                    upper = (*value * -1) as i16;
-                } else {
-                    upper = 0;
-                }
+                upper = match lower_node.kind {
+                    NodeType::Int {ref value} if *value < 0 => (*value * -1 - 1) as i16,
+                    _ => 0,
+                };
+            },
+            None => {
+                lower = 0;
+                upper = 0;
             },
-            _ => upper = 0,
         }
 
-        let dependencies = value_result.dependencies;
-        let changes = value_result.changes;
-
         let mut result_mapping = Mapping::new();
 
-        for (path, address) in value_result.result {
+        for &(ref path, ref address) in value_mapping {
             let t;
             let elements;
             {
-                let o = vm.get_object(&address);
+                let o = vm.get_object(address);
                 t = *o.get_extension().last().unwrap();
-                elements = o.slice_elements(lower, upper);
+                elements = o.slice_elements(lower, upper, step);
             }
             
             let n = vm.object_of_type_pointer(&t);
@@ -51,14 +103,9 @@ impl SliceExecutor for PythonSlice {
                 o.set_elements(collection);
             }
 
-            result_mapping.add_mapping(path, n);
+            result_mapping.add_mapping(path.clone(), n);
         }
 
-        ExecutionResult {
-            flow: FlowControl::Continue,
-            dependencies: dependencies,
-            changes: changes,
-            result: result_mapping,
-        }
+        result_mapping
     }
 }
\ No newline at end of file
@@ -1,5 +1,7 @@
 use core::*;
 
+use super::loop_else;
+
 use core::Path;
 use std::collections::btree_map::Entry;
 use std::collections::BTreeSet;
@@ -12,7 +14,8 @@ impl WhileExecutor for PythonWhile {
     fn execute(&self,
                env: Environment,
                test: &GastNode,
-               body: &GastNode)
+               body: &GastNode,
+               or_else: &GastNode)
                -> ExecutionResult {
         let Environment { vm, executors } = env;
 
@@ -42,7 +45,17 @@ impl WhileExecutor for PythonWhile {
             }
         }
 
-        self.branch(vm, executors, body, total_changes, total_dependencies)
+        vm.new_breaks();
+        let mut result = self.branch(vm, executors, body, total_changes, total_dependencies);
+        let breaks = vm.get_breaks();
+
+        let mut else_result = loop_else(vm, executors, or_else, breaks);
+
+        result.changes.append(&mut else_result.changes);
+        result.dependencies.append(&mut else_result.dependencies);
+        result.flow = else_result.flow;
+
+        result
     }
 }
 